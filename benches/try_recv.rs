@@ -0,0 +1,63 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+use mio_channel::RecvStatus;
+
+const BATCH: usize = 1024;
+
+fn drain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("drain");
+
+    group.bench_function("try_recv", |b| {
+        b.iter_batched(
+            || {
+                let (tx, rx) = mio_channel::channel();
+
+                for i in 0..BATCH {
+                    tx.send(i).unwrap();
+                }
+
+                (tx, rx)
+            },
+            |(_tx, rx)| {
+                let mut sum = 0;
+
+                while let Ok(i) = rx.try_recv() {
+                    sum += i;
+                }
+
+                black_box(sum)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("try_recv_fast", |b| {
+        b.iter_batched(
+            || {
+                let (tx, rx) = mio_channel::channel();
+
+                for i in 0..BATCH {
+                    tx.send(i).unwrap();
+                }
+
+                (tx, rx)
+            },
+            |(_tx, rx)| {
+                let mut sum = 0;
+
+                while let RecvStatus::Message(i) = rx.try_recv_fast() {
+                    sum += i;
+                }
+
+                black_box(sum)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, drain);
+
+criterion_main!(benches);
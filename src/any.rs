@@ -0,0 +1,73 @@
+use std::{any::Any, io, sync::mpsc};
+
+use mio::{event, Token};
+
+use crate::Receiver;
+
+trait AnySource: event::Source + Send {
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Send + 'static> AnySource for Receiver<T> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// A type-erased [`Receiver`].
+///
+/// It lets the receivers of different types be stored together, and registered with
+/// the [`mio::poll::Poll`] in the same way. Values are received by downcasting it
+/// into the original type with [`AnyReceiver::downcast_recv`].
+pub struct AnyReceiver {
+    inner: Box<dyn AnySource>,
+}
+
+impl AnyReceiver {
+    /// Erase the type of the [`Receiver`].
+    pub fn new<T: Send + 'static>(rx: Receiver<T>) -> Self {
+        Self { inner: Box::new(rx) }
+    }
+
+    /// Return `true` if the original type of the values is `T`.
+    pub fn is<T: 'static>(&self) -> bool {
+        self.inner.as_any().is::<Receiver<T>>()
+    }
+
+    /// Try to receive a value of the type `T`. It works just like [`Receiver::try_recv`].
+    ///
+    /// It returns `None` if the original type of the values is not `T`.
+    pub fn downcast_recv<T: 'static>(&self) -> Option<Result<T, mpsc::TryRecvError>> {
+        self.inner.as_any().downcast_ref::<Receiver<T>>().map(Receiver::try_recv)
+    }
+}
+
+impl<T: Send + 'static> From<Receiver<T>> for AnyReceiver {
+    fn from(rx: Receiver<T>) -> Self {
+        Self::new(rx)
+    }
+}
+
+impl event::Source for AnyReceiver {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        self.inner.deregister(registry)
+    }
+}
@@ -0,0 +1,93 @@
+use std::{future::Future, io, pin::Pin, sync::{mpsc, Arc, Mutex}, task::{Context, Poll, Waker}};
+
+use mio::{event::Source, Events, Interest, Token};
+
+use crate::{waker::lock, Receiver, SharedReceiver};
+
+/// A bridge from the [`mio::Poll`] owned by a dedicated thread to the asynchronous tasks elsewhere.
+///
+/// The poll thread hands every batch of the events over to [`MioBridge::dispatch`],
+/// which wakes up the task awaiting [`BridgedReceiver::recv_async`] on an event of the channel.
+/// Unlike [`crate::BatchStream`], it reuses the existing poll to drive the tasks.
+pub struct MioBridge {
+    token: Token,
+    task: Arc<Mutex<Option<Waker>>>,
+}
+
+impl MioBridge {
+    /// Register the `rx` with the `registry` by the `token`, and bridge its events to a [`BridgedReceiver`].
+    pub fn new<T>(rx: Receiver<T>, registry: &mio::Registry, token: Token) -> io::Result<(Self, BridgedReceiver<T>)> {
+        let mut rx = rx.into_shared();
+
+        rx.register(registry, token, Interest::READABLE)?;
+
+        let task = Arc::new(Mutex::new(None));
+
+        Ok((Self { token, task: task.clone() }, BridgedReceiver { rx, task }))
+    }
+
+    /// Wake up the task awaiting the value if the token of the channel is found among the `events`.
+    ///
+    /// It returns `true` if the token is found.
+    pub fn dispatch(&self, events: &Events) -> bool {
+        if !events.iter().any(|event| event.token() == self.token) {
+            return false;
+        }
+
+        // It's woken up after releasing the lock, since it may set itself back right away.
+        let task = lock(&self.task).take();
+
+        if let Some(task) = task {
+            task.wake();
+        }
+
+        true
+    }
+}
+
+/// The receiving half bridged by the [`MioBridge`], which can be awaited by an asynchronous task.
+pub struct BridgedReceiver<T> {
+    rx: SharedReceiver<T>,
+    task: Arc<Mutex<Option<Waker>>>,
+}
+
+impl<T> BridgedReceiver<T> {
+    /// Wait for a value until the [`MioBridge`] dispatches an event of the channel.
+    ///
+    /// It resolves to `None` once the channel is disconnected with nothing left.
+    pub fn recv_async(&self) -> RecvAsync<'_, T> {
+        RecvAsync { rx: self }
+    }
+
+    /// Try to receive a value. It works just like [`Receiver::try_recv`].
+    pub fn try_recv(&self) -> Result<T, mpsc::TryRecvError> {
+        self.rx.try_recv()
+    }
+}
+
+/// The future returned by [`BridgedReceiver::recv_async`].
+pub struct RecvAsync<'a, T> {
+    rx: &'a BridgedReceiver<T>,
+}
+
+impl<T> Future for RecvAsync<'_, T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.rx.try_recv() {
+            Ok(t) => return Poll::Ready(Some(t)),
+            Err(mpsc::TryRecvError::Disconnected) => return Poll::Ready(None),
+            Err(mpsc::TryRecvError::Empty) => {}
+        }
+
+        *lock(&self.rx.task) = Some(cx.waker().clone());
+
+        // An event dispatched right before setting the task may have found nothing to wake up,
+        // so that it must be looked for once again.
+        match self.rx.try_recv() {
+            Ok(t) => Poll::Ready(Some(t)),
+            Err(mpsc::TryRecvError::Disconnected) => Poll::Ready(None),
+            Err(mpsc::TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+}
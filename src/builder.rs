@@ -0,0 +1,145 @@
+use std::{io, sync::Arc, time::Duration};
+
+use crate::{channel::{self, Options}, MetricsRegistry, Receiver, Sender, SharedTokenSet, SyncSender, TriggerMode};
+
+/// A builder to configure a channel.
+///
+/// It gathers all the options of a channel in one place, while the free functions
+/// such as [`crate::channel`] remain as the shortcuts with no configuration.
+/// The default configuration is the same as the one of [`crate::channel`] and [`crate::sync_channel`].
+/// The number of the values pending in the channel is always tracked regardless of the configuration.
+#[derive(Debug, Default, Clone)]
+pub struct ChannelBuilder {
+    options: Options,
+}
+
+impl ChannelBuilder {
+    /// Create a builder with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deregister the [`Receiver`] by itself once it observes the channel disconnected.
+    ///
+    /// It's done by [`Receiver::try_recv`] only after all the values left in the channel are received.
+    pub fn auto_deregister_on_disconnect(mut self, enabled: bool) -> Self {
+        self.options.auto_deregister = enabled;
+
+        self
+    }
+
+    /// Wake up the [`mio::poll::Poll`] every `interval` once the [`Receiver`] is registered.
+    ///
+    /// See [`crate::channel_with_heartbeat`] for the details.
+    pub fn heartbeat(mut self, interval: Duration) -> Self {
+        self.options.heartbeat = Some(interval);
+
+        self
+    }
+
+    /// Wake up the [`mio::poll::Poll`] whenever nothing is sent for `idle` once the [`Receiver`] is registered.
+    ///
+    /// See [`crate::channel_with_idle_timeout`] for the details.
+    pub fn idle_timeout(mut self, idle: Duration) -> Self {
+        self.options.idle_timeout = Some(idle);
+
+        self
+    }
+
+    /// Coalesce the wakes, so that the [`mio::poll::Poll`] is woken up only by the first value
+    /// sent after the [`Receiver`] finds the channel empty.
+    ///
+    /// The values sent meanwhile are reported with the [`crate::WakeOutcome::Coalesced`].
+    /// Hence the [`Receiver`] must drain the channel until it's empty on every event.
+    pub fn coalesce(mut self, enabled: bool) -> Self {
+        self.options.coalesce = enabled;
+
+        self
+    }
+
+    /// Set when the [`Receiver`] causes the readable events, which is [`TriggerMode::Edge`] by default.
+    ///
+    /// See [`TriggerMode`] for the details.
+    pub fn trigger_mode(mut self, mode: TriggerMode) -> Self {
+        self.options.trigger_mode = mode;
+
+        self
+    }
+
+    /// Defer the wakes of the senders until nothing is sent for the linger `window`,
+    /// or `max_batch` values are sent, whichever comes first.
+    ///
+    /// It reduces the wakes of the chatty producers at the cost of the latency, so that
+    /// a burst within the `window` causes only one event. The deferred sends are reported
+    /// with the [`crate::WakeOutcome::Coalesced`]. A background thread fires the wakes,
+    /// and it's stopped when the last sender is dropped, which wakes up the [`mio::poll::Poll`] anyway.
+    pub fn linger(mut self, window: Duration, max_batch: usize) -> Self {
+        self.options.linger = Some((window, max_batch));
+
+        self
+    }
+
+    /// Call `f` with the I/O error whenever it occurs when waking up the [`mio::poll::Poll`],
+    /// which is otherwise ignored by the senders.
+    pub fn on_wake_error<F: Fn(&io::Error) + Send + Sync + 'static>(mut self, f: F) -> Self {
+        self.options.on_wake_error = Some(Arc::new(f));
+
+        self
+    }
+
+    /// Deny the blocking receives, e.g. [`Receiver::recv`], while the [`Receiver`] is registered,
+    /// making them panic rather than risk a deadlock, since they bypass the [`mio::poll::Poll`].
+    ///
+    /// It's meant for catching the mistake in the debug builds, which is disabled by default.
+    pub fn deny_blocking_recv(mut self, enabled: bool) -> Self {
+        self.options.deny_blocking_recv = enabled;
+
+        self
+    }
+
+    /// Name the channel, so that it's told from the others, e.g. in the [`MetricsRegistry`].
+    ///
+    /// See [`crate::channel_named`] for where else the name is shown.
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.options.name = Some(name.into());
+
+        self
+    }
+
+    /// Report the metrics of the channel into the `registry`, tagged by the name given by [`ChannelBuilder::name`].
+    ///
+    /// Every channel built by the builder is reported separately, even with the same name.
+    pub fn metrics_registry(mut self, registry: &MetricsRegistry) -> Self {
+        self.options.metrics = Some(registry.clone());
+
+        self
+    }
+
+    /// Record the [`mio::Token`] of the [`Receiver`] in the `set` on the registration,
+    /// so that it fails if another receiver recorded in the same set already uses it.
+    ///
+    /// See [`SharedTokenSet`] for the details.
+    pub fn token_set(mut self, set: &SharedTokenSet) -> Self {
+        self.options.token_set = Some(set.clone());
+
+        self
+    }
+
+    /// Drive the timeouts of the channel by the `clock` instead of the system time.
+    #[cfg(feature = "test-util")]
+    pub fn clock(mut self, clock: Arc<dyn crate::Clock>) -> Self {
+        self.options.clock = clock;
+
+        self
+    }
+
+    /// Create a pair of the [`Sender`] and the [`Receiver`].
+    pub fn build<T>(self) -> (Sender<T>, Receiver<T>) {
+        channel::channel_with(self.options)
+    }
+
+    /// Create a pair of the [`SyncSender`] and the [`Receiver`].
+    pub fn build_sync<T>(self, bound: usize) -> (SyncSender<T>, Receiver<T>) {
+        channel::sync_channel_with(bound, self.options)
+    }
+}
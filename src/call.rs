@@ -0,0 +1,44 @@
+use std::sync::mpsc;
+
+use crate::{channel, Receiver, Sender};
+
+/// A request sent by [`Sender::call`], carrying the sender of its reply.
+pub struct Call<Req, Resp> {
+    request: Req,
+    reply: Sender<Resp>,
+}
+
+impl<Req, Resp> Call<Req, Resp> {
+    /// Return the request.
+    pub fn request(&self) -> &Req {
+        &self.request
+    }
+
+    /// Send the reply back to the caller, consuming the call.
+    ///
+    /// It fails if the caller has dropped the [`Receiver`] of the reply.
+    pub fn reply(self, resp: Resp) -> Result<(), mpsc::SendError<Resp>> {
+        self.reply.send(resp)
+    }
+
+    /// Split the call into the request and the sender of the reply,
+    /// e.g. to reply from somewhere else after taking the request.
+    pub fn into_parts(self) -> (Req, Sender<Resp>) {
+        (self.request, self.reply)
+    }
+}
+
+impl<Req, Resp> Sender<Call<Req, Resp>> {
+    /// Send a request along with a fresh sender of its reply, and return the [`Receiver`] of the reply.
+    ///
+    /// The receiver can be registered with the [`mio::poll::Poll`] to be woken up by the reply,
+    /// or by the hangup if the call is dropped without the reply. It returns the request back
+    /// if the channel is disconnected.
+    pub fn call(&self, req: Req) -> Result<Receiver<Resp>, mpsc::SendError<Req>> {
+        let (reply, rx) = channel();
+
+        self.send(Call { request: req, reply }).map_err(|mpsc::SendError(call)| mpsc::SendError(call.request))?;
+
+        Ok(rx)
+    }
+}
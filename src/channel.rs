@@ -1,44 +1,1638 @@
-use std::{sync::{mpsc, Arc, Mutex}, io};
+use std::{sync::{mpsc, Arc, Mutex, Weak, atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering}}, io, ops::{Deref, DerefMut}, cell::{RefCell, RefMut}, collections::VecDeque, time::{Duration, Instant}, thread, fmt};
 
-use mio::{Waker, event, Token};
+use mio::{event, Token};
+
+use crate::{clock::{Clock, SystemClock}, metrics::{Metrics, MetricsRegistry}, ticker::Ticker, token::{SharedTokenSet, TokenClaim}, waker::{lock, Registration, Waker, WakerSlot}};
 
 /// Create a pair of the [`Sender`] and the [`Receiver`].
 /// 
 /// The [`Receiver`] implements the [`event::Source`] so that it can be registered
 /// with the [`mio::poll::Poll`], while the [`Sender`] doesn't.
 pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    channel_with(Options::default())
+}
+
+pub(crate) fn channel_with<T>(options: Options) -> (Sender<T>, Receiver<T>) {
     let (tx, rx) = mpsc::channel();
 
-    let waker = Arc::new(Mutex::new(None));
+    let shared = Shared::new(&options);
+
+    (Sender::new(tx, SenderRef::new(shared.clone())), Receiver::new(shared, rx, options))
+}
+
+/// Create a pair of the [`Sender`] and the [`Receiver`] already registered with the `registry`.
+///
+/// Unlike [`channel`], which creates the [`Waker`] lazily on the first registration,
+/// it creates the [`Waker`] right away, so that no value sent ahead of the registration
+/// can be missed by the [`mio::poll::Poll`]. Prefer it when the receiver is always going to be polled
+/// and the registry is at hand, and prefer [`channel`] otherwise.
+///
+/// Registering the [`Receiver`] afterwards is a no-op as long as it's given the same `token`.
+pub fn channel_eager<T>(registry: &mio::Registry, token: Token) -> io::Result<(Sender<T>, Receiver<T>)> {
+    Ok(channel_with_registration(Registration::new(registry, token)?))
+}
+
+/// Create a pair of the [`Sender`] and the [`Receiver`] already registered by the `registration`.
+pub(crate) fn channel_with_registration<T>(registration: Registration) -> (Sender<T>, Receiver<T>) {
+    let (tx, rx) = channel();
+
+    rx.shared.waker.set(registration);
+
+    (tx, rx)
+}
+
+/// Create a pair of the [`Sender`] and the [`Receiver`] woken up periodically.
+///
+/// Once the [`Receiver`] is registered, a background thread wakes up the [`mio::poll::Poll`]
+/// every `interval` even if nothing is sent, so that the poll loop doesn't need a separate timer.
+/// A wake by the heartbeat can be told from a wake by a value given that [`Receiver::try_recv`]
+/// finds the channel empty. The thread is stopped when the [`Receiver`] is dropped.
+pub fn channel_with_heartbeat<T>(interval: Duration) -> (Sender<T>, Receiver<T>) {
+    channel_with(Options { heartbeat: Some(interval), ..Options::default() })
+}
+
+/// Create a pair of the [`Sender`] and the [`Receiver`] woken up when the senders are idle.
+///
+/// Once the [`Receiver`] is registered, a background thread wakes up the [`mio::poll::Poll`]
+/// whenever nothing is sent for `idle`, so that a stalled producer can be detected.
+/// Every value sent resets the timer. A wake by the idleness can be told from a wake by a value
+/// given that [`Receiver::try_recv`] finds the channel empty. The thread is stopped
+/// when the [`Receiver`] is dropped.
+pub fn channel_with_idle_timeout<T>(idle: Duration) -> (Sender<T>, Receiver<T>) {
+    channel_with(Options { idle_timeout: Some(idle), ..Options::default() })
+}
+
+/// Create a pair of the [`Sender`] and the [`Receiver`] which defers the wakes until the registration.
+///
+/// Unlike [`channel`], whose wakes are lost until the [`Receiver`] is registered,
+/// it records a wake missed ahead of the registration, and fires it on the first registration.
+/// So the [`mio::poll::Poll`] is woken up right after the registration if anything is sent,
+/// or all the senders are dropped, before it. Prefer [`channel_eager`] if the registry is at hand.
+pub fn channel_deferred<T>() -> (Sender<T>, Receiver<T>) {
+    channel_with(Options { deferred: true, ..Options::default() })
+}
+
+/// Create a pair of the [`Sender`] and the [`Receiver`] of a channel labeled with the `name`.
+///
+/// The name is shown in their [`fmt::Debug`] output and the messages of the panics caused by them,
+/// so that the channel misbehaving can be told from the others. See [`crate::ChannelBuilder::name`]
+/// for naming it along with the other options, e.g. to tag the metrics.
+pub fn channel_named<T>(name: impl Into<String>) -> (Sender<T>, Receiver<T>) {
+    channel_with(Options { name: Some(name.into()), ..Options::default() })
+}
+
+/// Create a pair of the [`Sender`] and the [`Receiver`] keeping track of the size of the values pending.
+///
+/// The size of each value is measured by `size_of` when it's sent, and measured again
+/// when it's received, so that `size_of` must return the same size for the same value.
+/// The total is reported by [`Receiver::pending_bytes`], which lets the consumer bounded by memory
+/// throttle the producers rather than relying on the number of the values.
+pub fn sized_channel<T, F: Fn(&T) -> usize + Send + Sync + 'static>(size_of: F) -> (Sender<T>, Receiver<T>) {
+    let (mut tx, mut rx) = channel();
+
+    let size_of: SizeOf<T> = Arc::new(size_of);
+
+    tx.size_of = Some(size_of.clone());
+
+    rx.size_of = Some(size_of);
+
+    (tx, rx)
+}
+
+/// A function measuring the size of a value for [`sized_channel`].
+type SizeOf<T> = Arc<dyn Fn(&T) -> usize + Send + Sync>;
+
+/// Create a pair of the [`SyncSender`] and the [`Receiver`].
+///
+/// The [`Receiver`] implements the [`event::Source`] so that it can be registered
+/// with the [`mio::poll::Poll`], while the [`Sender`] doesn't.
+pub fn sync_channel<T>(bound: usize) -> (SyncSender<T>, Receiver<T>) {
+    sync_channel_with(bound, Options::default())
+}
+
+pub(crate) fn sync_channel_with<T>(bound: usize, options: Options) -> (SyncSender<T>, Receiver<T>) {
+    let (tx, rx) = mpsc::sync_channel(bound);
+
+    let shared = Shared::new(&options);
+
+    let mut rx = Receiver::new(shared.clone(), rx, options);
+
+    rx.bound = Some(bound);
+
+    (SyncSender { tx, shared: SenderRef::new(shared), bound }, rx)
+}
+
+/// Register all the receivers with the `registry`, each by the token given along with it.
+///
+/// It's transactional: if any of them fails, the ones registered so far are deregistered
+/// before returning the error, so that none of them is left registered by it.
+pub fn register_all<T>(registry: &mio::Registry, receivers: &mut [(&mut Receiver<T>, Token)]) -> io::Result<()> {
+    for i in 0..receivers.len() {
+        let (rx, token) = &mut receivers[i];
+
+        if let Err(e) = registry.register(*rx, *token, mio::Interest::READABLE) {
+            for (rx, _) in &mut receivers[..i] {
+                let _ = registry.deregister(*rx);
+            }
+
+            return Err(e);
+        }
+    }
+
+    Ok(())
+}
+
+/// When the [`Receiver`] causes the readable events, configured by [`crate::ChannelBuilder::trigger_mode`].
+///
+/// Both of them are implemented on top of the [`Waker`] regardless of the platform.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerMode {
+    /// Cause an event when a value is sent, so that the [`Receiver`] should drain the channel
+    /// until it's empty on every event. It's the default.
+    #[default]
+    Edge,
+    /// Cause an event as long as the channel is not empty, so that the [`Receiver`] may
+    /// receive only some of the values on an event. A batch received leaving the channel non-empty,
+    /// e.g. by [`Receiver::steal`] or [`Receiver::recv_many_timeout`], causes another event once
+    /// at its end, and every value sent causes an event even if the channel is built with
+    /// [`crate::ChannelBuilder::coalesce`]. The values received one by one cause no event,
+    /// so that a drain stopped early by [`Receiver::try_recv`] is resumed by [`Receiver::resume_later`].
+    Level,
+}
+
+/// A callback called with the I/O error occurred when waking up the [`mio::poll::Poll`].
+pub(crate) type WakeErrorCallback = Arc<dyn Fn(&io::Error) + Send + Sync>;
+
+/// The options of a channel configured by the [`crate::ChannelBuilder`].
+#[derive(Clone)]
+pub(crate) struct Options {
+    pub(crate) auto_deregister: bool,
+    pub(crate) heartbeat: Option<Duration>,
+    pub(crate) idle_timeout: Option<Duration>,
+    pub(crate) clock: Arc<dyn Clock>,
+    pub(crate) coalesce: bool,
+    pub(crate) on_wake_error: Option<WakeErrorCallback>,
+    pub(crate) deferred: bool,
+    pub(crate) trigger_mode: TriggerMode,
+    pub(crate) linger: Option<(Duration, usize)>,
+    pub(crate) name: Option<String>,
+    pub(crate) metrics: Option<MetricsRegistry>,
+    pub(crate) token_set: Option<SharedTokenSet>,
+    pub(crate) deny_blocking_recv: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            auto_deregister: false,
+            heartbeat: None,
+            idle_timeout: None,
+            clock: Arc::new(SystemClock),
+            coalesce: false,
+            on_wake_error: None,
+            deferred: false,
+            trigger_mode: TriggerMode::Edge,
+            linger: None,
+            name: None,
+            metrics: None,
+            token_set: None,
+            deny_blocking_recv: false,
+        }
+    }
+}
+
+impl fmt::Debug for Options {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Options")
+            .field("auto_deregister", &self.auto_deregister)
+            .field("heartbeat", &self.heartbeat)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("clock", &self.clock)
+            .field("coalesce", &self.coalesce)
+            .field("on_wake_error", &self.on_wake_error.is_some())
+            .field("deferred", &self.deferred)
+            .field("trigger_mode", &self.trigger_mode)
+            .field("linger", &self.linger)
+            .field("name", &self.name)
+            .field("metrics", &self.metrics)
+            .field("token_set", &self.token_set)
+            .field("deny_blocking_recv", &self.deny_blocking_recv)
+            .finish()
+    }
+}
+
+/// The state shared by the senders and the receiver of a channel.
+struct Shared {
+    waker: WakerSlot,
+    generation: Mutex<Arc<Generation>>,
+    pending: AtomicUsize,
+    bytes: AtomicUsize,
+    paused: AtomicBool,
+    draining: AtomicBool,
+    closed: AtomicBool,
+    coalesce: bool,
+    level: bool,
+    notified: AtomicBool,
+    on_wake_error: Option<WakeErrorCallback>,
+    space: WakerSlot,
+    space_registered: AtomicBool,
+    clock: Arc<dyn Clock>,
+    last_sent: Option<Mutex<Instant>>,
+    missed: Option<AtomicBool>,
+    #[cfg(feature = "stream")]
+    task: Mutex<Option<std::task::Waker>>,
+    #[cfg(feature = "sink")]
+    space_tasks: Mutex<Vec<std::task::Waker>>,
+    linger: Option<Linger>,
+    chained: Mutex<Option<Arc<mio::Waker>>>,
+    metrics: Option<Arc<Metrics>>,
+    on_senders_dropped: Mutex<Option<SendersDropped>>,
+    name: Option<String>,
+}
+
+/// A callback called once by the last sender dropped.
+type SendersDropped = Box<dyn FnOnce() + Send>;
+
+/// The state of the wakes deferred by [`crate::ChannelBuilder::linger`].
+struct Linger {
+    window: Duration,
+    max_batch: usize,
+    state: Mutex<LingerState>,
+    ticker: Mutex<Option<Ticker>>,
+}
+
+#[derive(Default)]
+struct LingerState {
+    count: usize,
+    last_sent: Option<Instant>,
+}
+
+impl Shared {
+    fn new(options: &Options) -> Arc<Self> {
+        let shared = Arc::new(Self {
+            waker: WakerSlot::default(),
+            generation: Mutex::new(Arc::default()),
+            pending: AtomicUsize::new(0),
+            bytes: AtomicUsize::new(0),
+            paused: AtomicBool::new(false),
+            draining: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
+            coalesce: options.coalesce && options.trigger_mode == TriggerMode::Edge,
+            level: options.trigger_mode == TriggerMode::Level,
+            notified: AtomicBool::new(false),
+            on_wake_error: options.on_wake_error.clone(),
+            space: WakerSlot::default(),
+            space_registered: AtomicBool::new(false),
+            clock: options.clock.clone(),
+            last_sent: options.idle_timeout.map(|_| Mutex::new(options.clock.now())),
+            missed: options.deferred.then(|| AtomicBool::new(false)),
+            #[cfg(feature = "stream")]
+            task: Mutex::new(None),
+            #[cfg(feature = "sink")]
+            space_tasks: Mutex::new(Vec::new()),
+            linger: options.linger.map(|(window, max_batch)| Linger {
+                window,
+                max_batch,
+                state: Mutex::new(LingerState::default()),
+                ticker: Mutex::new(None),
+            }),
+            chained: Mutex::new(None),
+            metrics: options.metrics.as_ref().map(|metrics| metrics.register(options.name.clone().unwrap_or_default())),
+            on_senders_dropped: Mutex::new(None),
+            name: options.name.clone(),
+        });
+
+        if let Some(linger) = &shared.linger {
+            let weak = Arc::downgrade(&shared);
+
+            let ticker = Ticker::spawn_at(options.clock.clone(), options.clock.now() + linger.window, move |deadline| {
+                Self::linger_tick(&weak, deadline)
+            });
+
+            *lock(&linger.ticker) = Some(ticker);
+        }
+
+        shared
+    }
+
+    /// Fire the wake deferred by the senders once the linger window closes, and return the next deadline.
+    fn linger_tick(shared: &Weak<Self>, deadline: Instant) -> Instant {
+        let Some(shared) = shared.upgrade() else {
+            return deadline;
+        };
+
+        let Some(linger) = &shared.linger else {
+            return deadline;
+        };
+
+        let mut state = lock(&linger.state);
+
+        match state.last_sent {
+            Some(last_sent) if last_sent + linger.window > deadline => last_sent + linger.window,
+            Some(_) => {
+                *state = LingerState::default();
+
+                drop(state);
+
+                shared.wake();
+
+                deadline + linger.window
+            }
+            None => deadline + linger.window,
+        }
+    }
+
+    /// Record a value sent, and wake up the [`mio::poll::Poll`].
+    fn sent(&self) -> WakeOutcome {
+        self.sent_many(1)
+    }
+
+    /// Wake up the poll once for the `n` values sent.
+    fn sent_many(&self, n: usize) -> WakeOutcome {
+        let outcome = self.wake_sent();
+
+        if let Some(metrics) = &self.metrics {
+            metrics.sent(n, &outcome);
+        }
+
+        outcome
+    }
+
+    fn wake_sent(&self) -> WakeOutcome {
+        if let Some(last_sent) = &self.last_sent {
+            *lock(last_sent) = self.clock.now();
+        }
+
+        // The receiver is going to pick it up by itself.
+        if self.draining.load(Ordering::SeqCst) {
+            return WakeOutcome::Coalesced;
+        }
+
+        if let Some(linger) = &self.linger {
+            let mut state = lock(&linger.state);
+
+            state.count += 1;
+
+            if state.count < linger.max_batch {
+                state.last_sent = Some(self.clock.now());
+
+                return WakeOutcome::Coalesced;
+            }
+
+            *state = LingerState::default();
+        }
+
+        self.wake()
+    }
+
+    /// Return the number of the senders of the current generation.
+    fn senders(&self) -> usize {
+        lock(&self.generation).senders.load(Ordering::Acquire)
+    }
+
+    /// Call the callback installed by [`Receiver::on_all_senders_dropped`], if any.
+    fn senders_dropped(&self) {
+        // It's called after releasing the lock, since it may install another one right away.
+        let f = lock(&self.on_senders_dropped).take();
+
+        if let Some(f) = f {
+            f();
+        }
+    }
+
+    /// Wake up the [`mio::poll::Poll`] waiting for the room to send, if any.
+    fn wake_space(&self) {
+        #[cfg(feature = "sink")]
+        self.wake_space_tasks();
+
+        if !self.space_registered.load(Ordering::Acquire) {
+            return;
+        }
+
+        self.space.wake();
+    }
+
+    /// Wake up all the tasks waiting for the room of the [`crate::SyncSink`]s, if any.
+    ///
+    /// They're all woken up, since the sinks share the channel, and the ones finding no room
+    /// set themselves back.
+    #[cfg(feature = "sink")]
+    fn wake_space_tasks(&self) {
+        // They're woken up after releasing the lock, since they may set themselves back right away.
+        let tasks = std::mem::take(&mut *lock(&self.space_tasks));
+
+        for task in tasks {
+            task.wake();
+        }
+    }
+
+    /// Wake up the task waiting for the [`crate::BatchStream`], if any.
+    #[cfg(feature = "stream")]
+    fn wake_task(&self) {
+        // It's woken up after releasing the lock, since it may set itself back right away.
+        let task = lock(&self.task).take();
+
+        if let Some(task) = task {
+            task.wake();
+        }
+    }
+
+    fn wake(&self) -> WakeOutcome {
+        #[cfg(feature = "stream")]
+        self.wake_task();
+
+        if self.paused.load(Ordering::Acquire) {
+            return WakeOutcome::Coalesced;
+        }
+
+        // The flag is cleared by the receiver once it finds the channel empty.
+        if self.coalesce && self.notified.swap(true, Ordering::SeqCst) {
+            return WakeOutcome::Coalesced;
+        }
+
+        if let Some(chained) = &*lock(&self.chained) {
+            let _ = chained.wake();
+        }
+
+        let outcome = self.waker.wake_or_mark(self.missed.as_ref());
+
+        match &outcome {
+            WakeOutcome::Failed(e) => {
+                if let Some(f) = &self.on_wake_error {
+                    f(e);
+                }
+            }
+            WakeOutcome::NoWaker => self.notified.store(false, Ordering::SeqCst),
+            _ => {}
+        }
+
+        outcome
+    }
+}
+
+/// What happened to the wake after a value is sent by [`Sender::send_with_outcome`].
+#[derive(Debug)]
+pub enum WakeOutcome {
+    /// The receiver is not registered, so that there's nothing to wake up.
+    NoWaker,
+    /// The [`mio::poll::Poll`] is woken up.
+    Woken,
+    /// The wake is folded into a later one, e.g. while the receiver is paused.
+    Coalesced,
+    /// Waking up the [`mio::poll::Poll`] failed with the I/O error.
+    Failed(io::Error),
+}
+
+/// A handle to the [`Shared`] state owned by a sender.
+///
+/// Both of the [`Sender`] and the [`SyncSender`] count on it, so that the
+/// last one of them wakes up the [`mio::poll::Poll`] when it's dropped.
+struct SenderRef {
+    shared: Arc<Shared>,
+    generation: Arc<Generation>,
+}
+
+/// The senders created at once, which are cut off together by [`Receiver::replace_sender`].
+#[derive(Default)]
+struct Generation {
+    senders: AtomicUsize,
+    // The sends in the middle, so that the receiver can wait for them before cutting them off.
+    in_flight: AtomicUsize,
+    closed: AtomicBool,
+}
+
+/// A guard counting a send in the middle of the [`Generation`].
+struct InFlight<'a>(&'a Generation);
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl SenderRef {
+    /// Create a handle of a new sender, joining the current generation.
+    fn new(shared: Arc<Shared>) -> Self {
+        let generation = lock(&shared.generation).clone();
+
+        Self::join(shared, generation)
+    }
+
+    fn join(shared: Arc<Shared>, generation: Arc<Generation>) -> Self {
+        generation.senders.fetch_add(1, Ordering::Relaxed);
+
+        Self { shared, generation }
+    }
+
+    /// Enter a send, unless the channel is closed or the generation is cut off.
+    ///
+    /// The send must be done while holding the guard returned.
+    fn enter(&self) -> Option<InFlight<'_>> {
+        self.generation.in_flight.fetch_add(1, Ordering::SeqCst);
+
+        let guard = InFlight(&self.generation);
+
+        match self.is_closed() {
+            true => None,
+            false => Some(guard),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.shared.closed.load(Ordering::SeqCst) || self.generation.closed.load(Ordering::SeqCst)
+    }
+}
+
+impl Deref for SenderRef {
+    type Target = Shared;
+
+    fn deref(&self) -> &Shared {
+        &self.shared
+    }
+}
+
+impl Clone for SenderRef {
+    fn clone(&self) -> Self {
+        Self::join(self.shared.clone(), self.generation.clone())
+    }
+}
+
+impl Drop for SenderRef {
+    fn drop(&mut self) {
+        if self.generation.senders.fetch_sub(1, Ordering::AcqRel) != 1 {
+            return;
+        }
+
+        // The generation cut off is disconnected on its own, and nothing is left to be woken for.
+        if Arc::ptr_eq(&self.generation, &lock(&self.shared.generation)) {
+            if let Some(linger) = &self.linger {
+                drop(lock(&linger.ticker).take());
+            }
+
+            self.wake();
+
+            self.senders_dropped();
+        }
+    }
+}
+
+/// A handle to the [`Shared`] state owned by the receiver.
+///
+/// It marks the channel closed when it's dropped, so that the senders waiting
+/// for the receiver to make progress can give up.
+struct ReceiverRef(Arc<Shared>);
+
+impl Deref for ReceiverRef {
+    type Target = Shared;
+
+    fn deref(&self) -> &Shared {
+        &self.0
+    }
+}
+
+impl Drop for ReceiverRef {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Release);
+
+        self.wake_space();
+    }
+}
+
+/// A wrapper of the [`mpsc::Receiver`].
+/// 
+/// It implements the [`event::Source`] so that it can be registered with the [`mio::poll::Poll`].
+/// It ignores the [`mio::Interest`] and always cause readable events.
+///
+/// It's also woken up when the last sender is dropped, so that the hangup
+/// can be observed by [`Receiver::try_recv`] as [`mpsc::TryRecvError::Disconnected`].
+pub struct Receiver<T> {
+    shared: ReceiverRef,
+    rx: mpsc::Receiver<T>,
+    slot: RefCell<Option<T>>,
+    buffered: RefCell<VecDeque<T>>,
+    capacity_hint: usize,
+    size_of: Option<SizeOf<T>>,
+    bound: Option<usize>,
+    auto_deregister: bool,
+    deny_blocking_recv: bool,
+    pub(crate) clock: Arc<dyn Clock>,
+    _heartbeat: Option<Ticker>,
+    _watchdog: Option<Ticker>,
+    claim: Option<TokenClaim>,
+    arrivals: Arrivals,
+}
+
+/// The arrivals of the values tracked for [`Receiver::arrival_rate`].
+struct Arrivals {
+    origin: Instant,
+    // The nanoseconds since the origin, offset by one, so that zero means no arrival yet.
+    last: AtomicU64,
+    // The bits of the moving average of the intervals in seconds, being NaN until the second arrival.
+    interval: AtomicU64,
+}
+
+impl Arrivals {
+    /// The weight of a new interval in the moving average.
+    const ALPHA: f64 = 0.2;
+
+    fn new(origin: Instant) -> Self {
+        Self { origin, last: AtomicU64::new(0), interval: AtomicU64::new(f64::NAN.to_bits()) }
+    }
+
+    fn arrived(&self, now: Instant) {
+        let nanos = now.saturating_duration_since(self.origin).as_nanos() as u64 + 1;
+
+        let last = self.last.swap(nanos, Ordering::AcqRel);
+
+        if last == 0 {
+            return;
+        }
+
+        let sample = nanos.saturating_sub(last) as f64 / 1e9;
+
+        let average = f64::from_bits(self.interval.load(Ordering::Acquire));
+
+        let average = match average.is_nan() {
+            true => sample,
+            false => Self::ALPHA * sample + (1.0 - Self::ALPHA) * average,
+        };
+
+        self.interval.store(average.to_bits(), Ordering::Release);
+    }
+
+    fn rate(&self) -> f64 {
+        let average = f64::from_bits(self.interval.load(Ordering::Acquire));
+
+        if average.is_nan() || average <= 0.0 {
+            return 0.0;
+        }
+
+        1.0 / average
+    }
+}
+
+impl<T> Receiver<T> {
+    fn new(shared: Arc<Shared>, rx: mpsc::Receiver<T>, options: Options) -> Self {
+        let heartbeat = options.heartbeat.map(|interval| {
+            let shared = shared.clone();
+
+            Ticker::spawn(options.clock.clone(), interval, move || {
+                shared.wake();
+            })
+        });
+
+        let watchdog = options.idle_timeout.map(|idle| {
+            let shared = shared.clone();
+
+            Ticker::spawn_at(options.clock.clone(), options.clock.now() + idle, move |deadline| {
+                let last_sent = shared.last_sent.as_ref().map_or(deadline, |last_sent| *lock(last_sent));
+
+                if last_sent + idle > deadline {
+                    return last_sent + idle;
+                }
+
+                shared.wake();
+
+                deadline + idle
+            })
+        });
+
+        Self {
+            shared: ReceiverRef(shared),
+            rx,
+            slot: RefCell::new(None),
+            buffered: RefCell::new(VecDeque::new()),
+            capacity_hint: 0,
+            size_of: None,
+            bound: None,
+            auto_deregister: options.auto_deregister,
+            deny_blocking_recv: options.deny_blocking_recv,
+            arrivals: Arrivals::new(options.clock.now()),
+            clock: options.clock,
+            _heartbeat: heartbeat,
+            _watchdog: watchdog,
+            claim: options.token_set.map(TokenClaim::new),
+        }
+    }
+
+    /// Try to receive a value. It works just like [`mpsc::Receiver::try_recv`].
+    ///
+    /// If the channel is built with [`crate::ChannelBuilder::auto_deregister_on_disconnect`],
+    /// observing the [`mpsc::TryRecvError::Disconnected`] deregisters the receiver as well,
+    /// and so does every other method receiving a value.
+    /// It's never observed until all the values left in the channel are received.
+    pub fn try_recv(&self) -> Result<T, mpsc::TryRecvError> {
+        match self.poll_rx() {
+            Err(mpsc::TryRecvError::Empty) if self.shared.coalesce => {
+                self.shared.notified.store(false, Ordering::SeqCst);
+
+                // A value sent right before clearing the flag may have skipped its wake,
+                // so that it must be looked for once again.
+                self.poll_rx()
+            }
+            result => result,
+        }
+    }
+
+    /// Try to receive a value just like [`Receiver::try_recv`], returning the flat [`RecvStatus`]
+    /// rather than the [`Result`], so that a hot drain loop can match on it in a single step.
+    ///
+    /// Unless the wakes are coalesced, it matches on the inner receiver directly,
+    /// skipping the intermediate [`Result`] of [`Receiver::try_recv`].
+    pub fn try_recv_fast(&self) -> RecvStatus<T> {
+        if self.shared.coalesce {
+            return self.try_recv().into();
+        }
+
+        if let Some(t) = self.take_buffered() {
+            return RecvStatus::Message(t);
+        }
+
+        match self.rx.try_recv() {
+            Ok(t) => RecvStatus::Message(self.received(t)),
+            Err(mpsc::TryRecvError::Empty) => RecvStatus::Empty,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.disconnected();
+
+                RecvStatus::Disconnected
+            }
+        }
+    }
+
+    /// Try to receive a value just like [`Receiver::try_recv`], converting the error by `f`,
+    /// so that it can be folded into the error type of the caller with `?`.
+    pub fn try_recv_map_err<E, F: FnOnce(mpsc::TryRecvError) -> E>(&self, f: F) -> Result<T, E> {
+        self.try_recv().map_err(f)
+    }
+
+    /// Try to receive a value, or return the default value if there's nothing to receive.
+    ///
+    /// Note that it can't tell a default value actually sent from nothing received,
+    /// nor an empty channel from a disconnected one.
+    pub fn recv_or_default(&self) -> T
+    where
+        T: Default,
+    {
+        self.try_recv().unwrap_or_default()
+    }
+
+    fn poll_rx(&self) -> Result<T, mpsc::TryRecvError> {
+        if let Some(t) = self.take_buffered() {
+            return Ok(t);
+        }
+
+        match self.rx.try_recv() {
+            Ok(t) => Ok(self.received(t)),
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.disconnected();
+
+                Err(mpsc::TryRecvError::Disconnected)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Wait for a value. It works just like [`mpsc::Receiver::recv`].
+    ///
+    /// Note that it blocks the current thread without the help of the [`mio::poll::Poll`].
+    ///
+    /// # Panics
+    ///
+    /// It panics if the receiver is registered with a poll, and the channel is built with
+    /// [`crate::ChannelBuilder::deny_blocking_recv`].
+    pub fn recv(&self) -> Result<T, mpsc::RecvError> {
+        if let Some(t) = self.take_buffered() {
+            return Ok(t);
+        }
+
+        self.check_blocking_recv();
+
+        match self.rx.recv() {
+            Ok(t) => Ok(self.received(t)),
+            Err(e) => {
+                self.disconnected();
+
+                Err(e)
+            }
+        }
+    }
+
+    /// Wait for a value until the `timeout`. It works just like [`mpsc::Receiver::recv_timeout`].
+    ///
+    /// Note that it blocks the current thread without the help of the [`mio::poll::Poll`].
+    ///
+    /// # Panics
+    ///
+    /// It panics just like [`Receiver::recv`].
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, mpsc::RecvTimeoutError> {
+        if let Some(t) = self.take_buffered() {
+            return Ok(t);
+        }
+
+        self.check_blocking_recv();
+
+        let deadline = self.clock.now() + timeout;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(self.clock.now());
+
+            if remaining.is_zero() {
+                return self.try_recv().map_err(|e| match e {
+                    mpsc::TryRecvError::Empty => mpsc::RecvTimeoutError::Timeout,
+                    mpsc::TryRecvError::Disconnected => mpsc::RecvTimeoutError::Disconnected,
+                });
+            }
+
+            match self.rx.recv_timeout(self.clock.wait_slice(remaining)) {
+                Ok(t) => return Ok(self.received(t)),
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    self.disconnected();
+
+                    return Err(mpsc::RecvTimeoutError::Disconnected);
+                }
+            }
+        }
+    }
+
+    /// Wait for up to `max` values until the `timeout`.
+    ///
+    /// It returns the values received, along with `true` if the channel is found disconnected,
+    /// which is the case with nothing received if it's already disconnected.
+    /// Note that it blocks the current thread without the help of the [`mio::poll::Poll`].
+    pub fn recv_many_timeout(&self, max: usize, timeout: Duration) -> (Vec<T>, bool) {
+        let deadline = self.clock.now() + timeout;
+
+        let mut batch = Vec::with_capacity(self.capacity_hint.min(max));
+
+        while batch.len() < max {
+            match self.recv_timeout(deadline.saturating_duration_since(self.clock.now())) {
+                Ok(t) => batch.push(t),
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return (batch, true),
+            }
+        }
+
+        self.batch_received();
+
+        (batch, false)
+    }
+
+    /// Take the first value buffered inside the receiver, which is still counted as pending.
+    ///
+    /// The values are buffered by [`Receiver::peek_all`], looked ahead by [`Receiver::recv_coalesced`], or put back.
+    fn take_buffered(&self) -> Option<T> {
+        let t = self.buffered.borrow_mut().pop_front()?;
+
+        Some(self.received(t))
+    }
+
+    /// Take the next value, either buffered or queued in the channel, without counting it as received.
+    fn take_unreceived(&self) -> Option<T> {
+        let t = self.buffered.borrow_mut().pop_front();
+
+        t.or_else(|| self.rx.try_recv().ok())
+    }
+
+    /// Put a value received back in front of the channel, so that it's received first once again.
+    ///
+    /// It's counted as pending again.
+    pub(crate) fn put_back(&self, t: T) {
+        self.shared.pending.fetch_add(1, Ordering::AcqRel);
+
+        if let Some(size_of) = &self.size_of {
+            self.shared.bytes.fetch_add(size_of(&t), Ordering::AcqRel);
+        }
+
+        self.buffered.borrow_mut().push_front(t);
+    }
+
+    /// Buffer all the values queued in the channel inside the receiver, and lend them without receiving.
+    ///
+    /// They're received by [`Receiver::commit`] after inspecting them, or by any other method
+    /// receiving a value, in the same order. They're still counted as pending meanwhile.
+    /// Note that the buffer grows as long as the values are buffered but not received,
+    /// since the values are moved out of the channel regardless of its bound.
+    pub fn peek_all(&mut self) -> &[T] {
+        let buffered = self.buffered.get_mut();
+
+        while let Ok(t) = self.rx.try_recv() {
+            buffered.push_back(t);
+        }
+
+        buffered.make_contiguous()
+    }
+
+    /// Receive up to `n` values buffered by [`Receiver::peek_all`].
+    pub fn commit(&self, n: usize) -> Vec<T> {
+        let batch = (0..n).map_while(|_| self.take_buffered()).collect();
+
+        self.batch_received();
+
+        batch
+    }
+
+    /// Cause another event if the channel is not empty yet, so that a drain stopped early
+    /// is resumed on the next poll rather than waiting for the next value sent.
+    ///
+    /// The channel built with [`TriggerMode::Level`] does so by itself at the end of a batch.
+    pub fn resume_later(&self) {
+        if self.shared.pending.load(Ordering::Acquire) > 0 {
+            self.shared.wake();
+        }
+    }
+
+    /// Resume the drain later at the end of a batch, if the channel is level triggered.
+    ///
+    /// It's done once for a batch rather than for every value received,
+    /// which would cost a wake for every single value.
+    pub(crate) fn batch_received(&self) {
+        if self.shared.level {
+            self.resume_later();
+        }
+    }
+
+    fn received(&self, t: T) -> T {
+        self.arrivals.arrived(self.clock.now());
+
+        self.shared.pending.fetch_sub(1, Ordering::AcqRel);
+
+        if let Some(size_of) = &self.size_of {
+            self.shared.bytes.fetch_sub(size_of(&t), Ordering::AcqRel);
+        }
+
+        self.shared.wake_space();
+
+        t
+    }
+
+    /// Panic if it's about to block while registered, which is likely to be a deadlock,
+    /// since the poll is never woken up for the thread blocked.
+    fn check_blocking_recv(&self) {
+        if self.deny_blocking_recv && self.is_registered() {
+            match &self.shared.name {
+                Some(name) => panic!("blocking receive on a receiver registered with a poll: {}", name),
+                None => panic!("blocking receive on a receiver registered with a poll"),
+            }
+        }
+    }
+
+    fn disconnected(&self) {
+        // Nothing is left or on its way anymore, so that the count drifted by the values
+        // received bypassing the receiver is settled.
+        if self.buffered.borrow().is_empty() {
+            self.shared.pending.store(0, Ordering::SeqCst);
+        }
+
+        if self.auto_deregister {
+            self.clear_registration();
+        }
+    }
+
+    /// Try to receive a batch of up to half of the values pending in the channel, rounded up.
+    ///
+    /// It's meant for the workers sharing a [`crate::SharedReceiver`], so that each of them
+    /// can grab a batch at once rather than contending for every single value.
+    pub fn steal(&self) -> Vec<T> {
+        let n = self.len().div_ceil(2);
+
+        let mut batch = Vec::with_capacity(n.max(self.capacity_hint));
+
+        while batch.len() < n {
+            match self.try_recv() {
+                Ok(t) => batch.push(t),
+                Err(_) => break,
+            }
+        }
+
+        self.batch_received();
+
+        batch
+    }
+
+    /// Receive all the values available, calling `f` with each of them.
+    ///
+    /// It returns `Ok(())` once the channel is drained, or [`mpsc::TryRecvError::Disconnected`]
+    /// once it's found disconnected with nothing left. Note that the values already received
+    /// are gone if `f` panics in the middle.
+    pub fn on_message<F: FnMut(T)>(&self, mut f: F) -> Result<(), mpsc::TryRecvError> {
+        loop {
+            match self.try_recv() {
+                Ok(t) => f(t),
+                Err(mpsc::TryRecvError::Empty) => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Receive all the values available, calling `on_message` with each of them,
+    /// and `on_disconnect` once the channel is found disconnected.
+    ///
+    /// It works just like [`Receiver::on_message`], so that `on_disconnect` is called only
+    /// after all the values left are received. It returns `true` if `on_disconnect` is called.
+    /// Note that every call finding the channel disconnected calls its own `on_disconnect`.
+    pub fn split_handler<M: FnMut(T), D: FnOnce()>(&self, on_message: M, on_disconnect: D) -> bool {
+        match self.on_message(on_message) {
+            Ok(()) => false,
+            Err(_) => {
+                on_disconnect();
+
+                true
+            }
+        }
+    }
+
+    /// Try to receive the first value satisfying the predicate `keep`.
+    ///
+    /// The values failing the predicate are received and dropped for good,
+    /// until one of them satisfies it or the channel is found empty.
+    /// It returns the value found, if any, along with the number of the values dropped.
+    pub fn recv_filtered<F: FnMut(&T) -> bool>(&self, mut keep: F) -> (Option<T>, usize) {
+        let mut discarded = 0;
+
+        while let Ok(t) = self.try_recv() {
+            if keep(&t) {
+                return (Some(t), discarded);
+            }
+
+            discarded += 1;
+        }
+
+        (None, discarded)
+    }
+
+    /// Drain the values available into the accumulator by `f`, starting with `init`,
+    /// and return the final accumulator.
+    ///
+    /// It stops once the channel is found empty or disconnected, without allocating a batch.
+    pub fn fold_messages<A, F: FnMut(A, T) -> A>(&self, init: A, mut f: F) -> A {
+        let mut acc = init;
+
+        while let Ok(t) = self.try_recv() {
+            acc = f(acc, t);
+        }
+
+        acc
+    }
+
+    /// Drain all the values available, and return them sorted.
+    ///
+    /// It's meant for the producers emitting the values out of order, e.g. by priority.
+    /// Note that they're only sorted within a single call, not across the calls.
+    pub fn drain_sorted(&self) -> Vec<T>
+    where
+        T: Ord,
+    {
+        let mut values = self.fold_messages(Vec::new(), |mut values, t| {
+            values.push(t);
+
+            values
+        });
+
+        values.sort();
+
+        values
+    }
+
+    /// Receive the values available into the slots of `buf`, and return the number of the values written.
+    ///
+    /// It fills the slots from the start, until all of them are filled or the channel is found empty
+    /// or disconnected, without allocating a batch. The slots left are untouched.
+    pub fn recv_into(&self, buf: &mut [Option<T>]) -> usize {
+        let mut n = 0;
+
+        for slot in buf.iter_mut() {
+            match self.try_recv() {
+                Ok(t) => *slot = Some(t),
+                Err(_) => break,
+            }
+
+            n += 1;
+        }
+
+        n
+    }
+
+    /// Try to receive a value, discarding the values equal to it right behind.
+    ///
+    /// Only a run of the equal values adjacent to each other is collapsed into one,
+    /// so that `A A B A` is received as `A B A`. The first value differing from the run
+    /// is buffered inside the receiver, and received first by the next call of any method.
+    /// It returns nothing if the channel is empty or disconnected.
+    pub fn recv_coalesced(&self) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        let first = self.try_recv().ok()?;
+
+        while let Some(t) = self.take_unreceived() {
+            if t != first {
+                self.buffered.borrow_mut().push_front(t);
+
+                break;
+            }
+
+            self.received(t);
+        }
+
+        Some(first)
+    }
+
+    /// Close the channel, and drain all the values left in it.
+    ///
+    /// Every value sent after it fails, as if the receiver were dropped.
+    /// The values already on their way when it's closed are drained as well, since it keeps
+    /// draining while any value is pending, for a short grace window after finding the channel empty.
+    /// So it may block a moment for a sender in the middle of sending. The window is bounded,
+    /// since the pending count can be off, e.g. after receiving through [`Receiver::as_ref`].
+    pub fn drain_and_close(&self) -> Vec<T> {
+        self.shared.closed.store(true, Ordering::SeqCst);
+
+        let mut values = Vec::new();
+
+        let mut deadline = None;
+
+        loop {
+            match self.try_recv() {
+                Ok(t) => {
+                    values.push(t);
+
+                    deadline = None;
+                }
+                Err(mpsc::TryRecvError::Empty) if self.shared.pending.load(Ordering::SeqCst) > 0 => {
+                    // It's bounded in the real time, since a manual clock may never advance meanwhile.
+                    let deadline = *deadline.get_or_insert_with(|| Instant::now() + Self::DRAIN_GRACE);
+
+                    if Instant::now() >= deadline {
+                        break;
+                    }
+
+                    thread::yield_now();
+                }
+                Err(_) => break,
+            }
+        }
+
+        values
+    }
+
+    /// The longest time for [`Receiver::drain_and_close`] to wait for the values pending once it finds the channel empty.
+    const DRAIN_GRACE: Duration = Duration::from_millis(10);
+
+    /// Set the capacity the batches are allocated with, so that they're not reallocated
+    /// as long as the load stays below it.
+    ///
+    /// It applies to [`Receiver::recv_many_timeout`] and [`Receiver::steal`].
+    pub fn set_capacity_hint(&mut self, n: usize) {
+        self.capacity_hint = n;
+    }
+
+    /// Pause waking up the [`mio::poll::Poll`] without deregistering the receiver.
+    ///
+    /// The values sent meanwhile are still queued in the channel.
+    pub fn pause(&self) {
+        self.shared.paused.store(true, Ordering::Release);
+    }
+
+    /// Resume waking up the [`mio::poll::Poll`] paused by [`Receiver::pause`].
+    ///
+    /// It wakes up the poll once if any value is queued or the senders are gone meanwhile,
+    /// so that they can be received with a single event.
+    pub fn resume(&self) {
+        self.shared.paused.store(false, Ordering::Release);
+
+        if !self.is_empty() || self.shared.senders() == 0 {
+            self.shared.wake();
+        }
+    }
+
+    /// Tell the senders that the receiver is actively draining the channel, e.g. spinning on
+    /// [`Receiver::try_recv`] between the polls, so that they skip the wakes meanwhile.
+    ///
+    /// Clearing it wakes up the [`mio::poll::Poll`] once if any value is queued, just like
+    /// [`Receiver::resume`]. Beware that forgetting to clear it leaves the values sent
+    /// without any wake, so that the poll never notices them.
+    pub fn set_actively_draining(&self, draining: bool) {
+        self.shared.draining.store(draining, Ordering::SeqCst);
+
+        if !draining && !self.is_empty() {
+            self.shared.wake();
+        }
+    }
+
+    /// Register the receiver with the `registry`, and return a [`ReceiverGuard`]
+    /// deregistering it when the guard goes out of scope.
+    ///
+    /// It makes sure that the receiver is deregistered on an early return or a panic.
+    /// Since the guard borrows the `registry` meanwhile, give it a clone by [`mio::Registry::try_clone`]
+    /// to keep polling the [`mio::poll::Poll`] owning it.
+    pub fn register_scoped<'r>(&'r mut self, registry: &'r mio::Registry, token: Token) -> io::Result<ReceiverGuard<'r, T>> {
+        registry.register(self, token, mio::Interest::READABLE)?;
+
+        Ok(ReceiverGuard { rx: self, registry })
+    }
+
+    /// Return `true` if a wake has been issued since the channel was last found empty,
+    /// so that the values sent meanwhile are coalesced into it.
+    ///
+    /// It's always `false` unless the channel is built with [`crate::ChannelBuilder::coalesce`].
+    pub fn is_wake_pending(&self) -> bool {
+        self.shared.coalesce && self.shared.notified.load(Ordering::SeqCst)
+    }
+
+    /// Acknowledge the last wake, so that the next value sent wakes up the [`mio::poll::Poll`] again.
+    ///
+    /// It's meant to be called after handling an event of a coalescing channel.
+    /// If any value is still left in the channel, it wakes up the poll again right away,
+    /// so that the values are never stranded even if the channel is not fully drained.
+    pub fn acknowledge(&self) {
+        self.shared.notified.store(false, Ordering::SeqCst);
+
+        if !self.is_empty() {
+            self.shared.wake();
+        }
+    }
+
+    /// Cut off the current senders, and return a new [`Sender`] to the receiver.
+    ///
+    /// The receiver keeps its registration, so that the [`mio::poll::Poll`] is woken up
+    /// by the new sender without registering it again. The current senders fail from then on,
+    /// and the values left in the channel are dropped, as well as the ones racing with it,
+    /// which it waits for a moment. The new sender and its clones are counted apart from
+    /// the ones cut off, so that dropping all of them hangs up the channel on its own.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the channel is bounded, since the new one would lose the bound.
+    /// Use [`Receiver::replace_sync_sender`] instead.
+    pub fn replace_sender(&mut self) -> Sender<T> {
+        if self.bound.is_some() {
+            match &self.shared.name {
+                Some(name) => panic!("replacing the sender of a bounded channel with an unbounded one: {}", name),
+                None => panic!("replacing the sender of a bounded channel with an unbounded one"),
+            }
+        }
+
+        let (tx, rx) = mpsc::channel();
+
+        self.cut_off(rx);
+
+        let mut tx = Sender::new(tx, SenderRef::new(self.shared.0.clone()));
+
+        tx.size_of = self.size_of.clone();
+
+        tx
+    }
+
+    /// Cut off the current senders, and return a new [`SyncSender`] to the receiver of a bounded channel.
+    ///
+    /// It works just like [`Receiver::replace_sender`], while the new channel keeps the bound.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the channel is unbounded. Use [`Receiver::replace_sender`] instead.
+    pub fn replace_sync_sender(&mut self) -> SyncSender<T> {
+        let Some(bound) = self.bound else {
+            match &self.shared.name {
+                Some(name) => panic!("replacing the sender of an unbounded channel with a bounded one: {}", name),
+                None => panic!("replacing the sender of an unbounded channel with a bounded one"),
+            }
+        };
+
+        let (tx, rx) = mpsc::sync_channel(bound);
+
+        self.cut_off(rx);
+
+        SyncSender { tx, shared: SenderRef::new(self.shared.0.clone()), bound }
+    }
+
+    /// Swap in the inner receiver `rx` of a new channel, cutting off the current senders.
+    fn cut_off(&mut self, rx: mpsc::Receiver<T>) {
+        let generation = std::mem::take(&mut *lock(&self.shared.generation));
+
+        generation.closed.store(true, Ordering::SeqCst);
+
+        let old = std::mem::replace(&mut self.rx, rx);
+
+        // The sends in the middle are waited for, so that every value counted as pending is received
+        // and dropped here. It keeps draining meanwhile, since a bounded one may be blocked on the room.
+        loop {
+            let done = generation.in_flight.load(Ordering::SeqCst) == 0;
+
+            while let Ok(t) = old.try_recv() {
+                self.received(t);
+            }
+
+            if done {
+                break;
+            }
+
+            thread::yield_now();
+        }
+
+        drop(old);
+    }
+
+    /// Reset the receiver to reuse it for another lifecycle, and return a new [`Sender`] to it.
+    ///
+    /// It works just like [`Receiver::replace_sender`], keeping the registration, while it reopens
+    /// the channel closed by [`Receiver::drain_and_close`] as well. All the values buffered,
+    /// including the ones buffered inside the receiver, are discarded, so that nothing is pending.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the channel is bounded, just like [`Receiver::replace_sender`].
+    pub fn reset(&mut self) -> Sender<T> {
+        while self.take_buffered().is_some() {}
+
+        let tx = self.replace_sender();
+
+        self.shared.closed.store(false, Ordering::SeqCst);
+
+        self.shared.notified.store(false, Ordering::SeqCst);
+
+        tx
+    }
+
+    /// Split the receiver into the inner [`mpsc::Receiver`] and the [`ReceiverWaker`] the senders wake,
+    /// so that they can be reassembled into a custom source.
+    ///
+    /// The channel is kept open as long as the waker is alive, and it keeps the registration of the
+    /// receiver too. The caller is responsible for upholding what the receiver used to do:
+    /// the values buffered inside the receiver, e.g. by [`Receiver::peek_all`], are discarded,
+    /// the pending count is never decremented, and the coalesced wakes have to be rearmed
+    /// by [`ReceiverWaker::rearm`] after draining, or no more wakes are caused.
+    /// The token claimed from a [`SharedTokenSet`] is released as well.
+    pub fn into_parts(self) -> (mpsc::Receiver<T>, ReceiverWaker) {
+        (self.rx, ReceiverWaker { shared: self.shared })
+    }
+
+    /// Return the [`mio::Interest`] of the events the receiver causes, which is always readable.
+    ///
+    /// Note that the interest given on the registration is ignored.
+    pub fn supported_interest(&self) -> mio::Interest {
+        mio::Interest::READABLE
+    }
+
+    /// Return `true` if the receiver is registered with the `registry` by the `token`.
+    ///
+    /// Mio doesn't expose the identity of a registry, so that it's told by an opaque id
+    /// captured on the registration. Note that a clone by [`mio::Registry::try_clone`]
+    /// is told from the original one, even though they belong to the same [`mio::poll::Poll`].
+    pub fn is_registered_with(&self, registry: &mio::Registry, token: Token) -> bool {
+        self.shared.waker.is_registered_with(registry, token)
+    }
+
+    /// Return the [`Token`] the receiver is registered with, if any.
+    pub fn token(&self) -> Option<Token> {
+        self.shared.waker.token()
+    }
+
+    /// Return `true` if the receiver is registered with a [`mio::poll::Poll`].
+    pub fn is_registered(&self) -> bool {
+        self.shared.waker.is_registered()
+    }
+
+    /// Return the [`Waker`] the receiver is currently registered with, if any.
+    ///
+    /// It can be shared with other components to wake up the same [`mio::poll::Poll`].
+    /// Note that waking it up manually causes a spurious event of the receiver,
+    /// which the consumer should tolerate by observing [`mpsc::TryRecvError::Empty`].
+    pub fn waker(&self) -> Option<Arc<Waker>> {
+        self.shared.waker.waker()
+    }
+
+    /// Install the callback `f` called once the last sender is dropped.
+    ///
+    /// It's called exactly once by the [`Drop`] of the last sender, on the thread dropping it,
+    /// rather than by the drops of the clones ahead of it. It's called right away if all the senders
+    /// are already gone. It replaces the one installed before, if any.
+    pub fn on_all_senders_dropped<F: FnOnce() + Send + 'static>(&self, f: F) {
+        *lock(&self.shared.on_senders_dropped) = Some(Box::new(f));
+
+        if self.shared.senders() == 0 {
+            self.shared.senders_dropped();
+        }
+    }
+
+    /// Chain the `extra` waker, so that it's woken up along with the registration by every wake.
+    ///
+    /// It replaces the one chained before, if any. It's woken up even if the receiver
+    /// is not registered, while it's not woken up while the receiver is paused, nor by
+    /// the wakes coalesced. Note that its I/O error is ignored.
+    pub fn chain_waker(&self, extra: Arc<mio::Waker>) {
+        *lock(&self.shared.chained) = Some(extra);
+    }
+
+    /// Remove the waker chained by [`Receiver::chain_waker`], if any.
+    pub fn unchain_waker(&self) {
+        *lock(&self.shared.chained) = None;
+    }
+
+    /// Set the task to be woken up along with the [`mio::poll::Poll`] by the next wake.
+    #[cfg(feature = "stream")]
+    pub(crate) fn set_task(&self, task: &std::task::Waker) {
+        *lock(&self.shared.task) = Some(task.clone());
+    }
+
+    fn clear_registration(&self) {
+        self.shared.waker.clear();
+
+        if let Some(claim) = &self.claim {
+            claim.release();
+        }
+    }
+
+    /// Claim the `token` in the [`SharedTokenSet`], if any, ahead of the registration.
+    fn claim(&self, token: Token) -> io::Result<()> {
+        match &self.claim {
+            Some(claim) => claim.claim(token, self.shared.name.as_deref()),
+            None => Ok(()),
+        }
+    }
+
+    /// Try to receive a value into the slot inside the receiver,
+    /// and return a [`RecvGuard`] lending it by reference.
+    ///
+    /// The value is dropped along with the guard, so that a value expensive to move
+    /// can be processed in place without being moved into another storage.
+    /// It returns `None` if the channel is empty or disconnected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the previous guard is still alive.
+    pub fn recv_guard(&self) -> Option<RecvGuard<'_, T>> {
+        let mut slot = self.slot.borrow_mut();
+
+        *slot = Some(self.try_recv().ok()?);
+
+        Some(RecvGuard { slot })
+    }
+
+    /// Return the rate of the values received, in the values per second.
+    ///
+    /// It's an exponentially weighted moving average of the intervals between the values,
+    /// measured when they're received rather than sent. So it's only approximate, and it's
+    /// zero until the second value is received, or if they're received all at once.
+    pub fn arrival_rate(&self) -> f64 {
+        self.arrivals.rate()
+    }
+
+    /// Return the number of the values pending in the channel.
+    ///
+    /// Note that it's only a snapshot, which can be changed by the senders at any time.
+    /// It's off by the values received bypassing the receiver, e.g. through [`Receiver::as_ref`],
+    /// until the channel is found disconnected.
+    pub fn len(&self) -> usize {
+        self.shared.pending.load(Ordering::Acquire)
+    }
+
+    /// Return `true` if there is no value pending in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the total size of the values pending in the channel created by [`sized_channel`].
+    ///
+    /// It's always zero for the other channels.
+    /// Note that it's only a snapshot, which can be changed by the senders at any time.
+    pub fn pending_bytes(&self) -> usize {
+        self.shared.bytes.load(Ordering::Acquire)
+    }
+}
 
-    (Sender { waker: waker.clone(), tx }, Receiver { waker, rx })
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("name", &self.shared.name)
+            .field("len", &self.len())
+            .field("registered", &self.is_registered())
+            .finish_non_exhaustive()
+    }
 }
 
-/// Create a pair of the [`SyncSender`] and the [`Receiver`].
+impl<T> AsRef<mpsc::Receiver<T>> for Receiver<T> {
+    /// Lend the inner [`mpsc::Receiver`] to the code expecting it.
+    ///
+    /// Note that the values received through it bypass the receiver, so that they're never
+    /// observed by any feature of the receiver. Nor does it see the values buffered inside
+    /// the receiver, e.g. by [`Receiver::peek_all`].
+    ///
+    /// Every value received through it is still counted as pending, so that [`Receiver::len`]
+    /// and [`Receiver::is_empty`] stay off by it until the channel is found disconnected.
+    /// The methods relying on them tolerate the drift: [`Receiver::resume`], [`Receiver::acknowledge`],
+    /// [`Receiver::set_actively_draining`] and the [`TriggerMode::Level`] may cause spurious wakes,
+    /// [`Receiver::steal`] may grab a smaller batch, and [`Receiver::drain_and_close`] waits
+    /// only for its bounded grace window.
+    fn as_ref(&self) -> &mpsc::Receiver<T> {
+        &self.rx
+    }
+}
+
+/// The waker split from a [`Receiver`] by [`Receiver::into_parts`].
 ///
-/// The [`Receiver`] implements the [`event::Source`] so that it can be registered
-/// with the [`mio::poll::Poll`], while the [`Sender`] doesn't.
-pub fn sync_channel<T>(bound: usize) -> (SyncSender<T>, Receiver<T>) {
-    let (tx, rx) = mpsc::sync_channel(bound);
+/// It implements the [`event::Source`] just like the receiver, and dropping it closes the channel.
+pub struct ReceiverWaker {
+    shared: ReceiverRef,
+}
+
+impl ReceiverWaker {
+    /// Wake up the [`mio::poll::Poll`], e.g. after sending a value through a raw sender.
+    pub fn wake(&self) -> WakeOutcome {
+        self.shared.wake()
+    }
 
-    let waker = Arc::new(Mutex::new(None));
+    /// Return the [`Waker`] registered, if any.
+    pub fn waker(&self) -> Option<Arc<Waker>> {
+        self.shared.waker.waker()
+    }
 
-    (SyncSender { waker: waker.clone(), tx }, Receiver { waker, rx })
+    /// Allow the next send to wake up the poll again, if the wakes are coalesced.
+    pub fn rearm(&self) {
+        self.shared.notified.store(false, Ordering::SeqCst);
+    }
 }
 
-/// A wrapper of the [`mpsc::Receiver`].
-/// 
-/// It implements the [`event::Source`] so that it can be registered with the [`mio::poll::Poll`].
-/// It ignores the [`mio::Interest`] and always cause readable events.
-pub struct Receiver<T> {
-    waker: Arc<Mutex<Option<Waker>>>,
-    rx: mpsc::Receiver<T>
+impl event::Source for ReceiverWaker {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        _: mio::Interest,
+    ) -> io::Result<()> {
+        if self.shared.waker.register(registry, token, self.shared.name.as_deref())? {
+            self.rearm();
+        }
+
+        Ok(())
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        _: mio::Interest,
+    ) -> io::Result<()> {
+        self.shared.waker.reregister(registry, token)?;
+
+        self.rearm();
+
+        Ok(())
+    }
+
+    fn deregister(&mut self, _: &mio::Registry) -> io::Result<()> {
+        self.shared.waker.clear();
+
+        Ok(())
+    }
 }
 
-impl<T> Receiver<T> {
-    /// Try to receive a value. It works just like [`mpsc::Receiver::try_recv`].
-    pub fn try_recv(&self) -> Result<T, mpsc::TryRecvError> {
-        self.rx.try_recv()
+/// A guard lending a value received by [`Receiver::recv_guard`].
+///
+/// It dereferences to the value, and drops it when it goes out of scope.
+pub struct RecvGuard<'a, T> {
+    slot: RefMut<'a, Option<T>>,
+}
+
+impl<T> Deref for RecvGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.slot.as_ref().expect("the slot of a guard is always filled")
+    }
+}
+
+impl<T> Drop for RecvGuard<'_, T> {
+    fn drop(&mut self) {
+        *self.slot = None;
+    }
+}
+
+/// A guard deregistering the [`Receiver`] registered by [`Receiver::register_scoped`].
+///
+/// It dereferences to the [`Receiver`], and deregisters it when it goes out of scope.
+pub struct ReceiverGuard<'r, T> {
+    rx: &'r mut Receiver<T>,
+    registry: &'r mio::Registry,
+}
+
+impl<T> Deref for ReceiverGuard<'_, T> {
+    type Target = Receiver<T>;
+
+    fn deref(&self) -> &Receiver<T> {
+        self.rx
+    }
+}
+
+impl<T> DerefMut for ReceiverGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Receiver<T> {
+        self.rx
+    }
+}
+
+impl<T> Drop for ReceiverGuard<'_, T> {
+    fn drop(&mut self) {
+        let _ = self.registry.deregister(self.rx);
     }
 }
 
@@ -49,10 +1643,26 @@ impl<T> event::Source for Receiver<T> {
         token: Token,
         _: mio::Interest,
     ) -> io::Result<()> {
-        let mut waker = self.waker.lock().unwrap();
+        let registered = self.is_registered();
+
+        if !registered {
+            self.claim(token)?;
+        }
+
+        let result = self.shared.waker.register(registry, token, self.shared.name.as_deref());
 
-        if waker.is_none() {
-            *waker = Some(Waker::new(registry, token)?);
+        if result.is_err() && !registered {
+            self.clear_registration();
+        }
+
+        if result? {
+            self.shared.notified.store(false, Ordering::SeqCst);
+
+            if let Some(missed) = &self.shared.missed {
+                if missed.swap(false, Ordering::SeqCst) {
+                    self.shared.wake();
+                }
+            }
         }
 
         Ok(())
@@ -64,76 +1674,509 @@ impl<T> event::Source for Receiver<T> {
         token: Token,
         _: mio::Interest,
     ) -> io::Result<()> {
-        let mut waker = self.waker.lock().unwrap();
+        self.claim(token)?;
+
+        self.shared.waker.reregister(registry, token)?;
+
+        self.shared.notified.store(false, Ordering::SeqCst);
 
-        *waker = Some(Waker::new(registry, token)?);
-     
         Ok(())
     }
 
     fn deregister(&mut self, _: &mio::Registry) -> io::Result<()> {
-        let mut waker = self.waker.lock().unwrap();
-
-        *waker = None;
+        self.clear_registration();
 
         Ok(())
     }
 }
 
 /// A wrapper of the [`mpsc::Sender`].
+///
+/// Dropping the last one wakes up the [`mio::poll::Poll`].
 pub struct Sender<T> {
-    waker: Arc<Mutex<Option<Waker>>>,
-    tx: mpsc::Sender<T>
+    // The inner sender must be declared ahead of the shared state,
+    // so that the channel is already disconnected by the time the last one wakes up the poll.
+    tx: mpsc::Sender<T>,
+    shared: SenderRef,
+    delivered: AtomicBool,
+    size_of: Option<SizeOf<T>>,
 }
 
 impl<T> Sender<T> {
+    fn new(tx: mpsc::Sender<T>, shared: SenderRef) -> Self {
+        Self { tx, shared, delivered: AtomicBool::new(false), size_of: None }
+    }
+
     /// Try to send a value. It works just like [`mpsc::Sender::send`].
     /// After sending it, it's waking upthe [`mio::poll::Poll`].
     /// 
     /// Note that it does not return any I/O error even if it occurs
     /// when waking up the [`mio::poll::Poll`].
     pub fn send(&self, t: T) -> Result<(), mpsc::SendError<T>> {
-        self.tx.send(t)?;
+        self.send_with_outcome(t)?;
+
+        Ok(())
+    }
+
+    /// Try to send a value just like [`Sender::send`],
+    /// and return what happened to the wake after sending it.
+    pub fn send_with_outcome(&self, t: T) -> Result<WakeOutcome, mpsc::SendError<T>> {
+        self.enqueue(t)?;
+
+        let outcome = self.shared.sent();
+
+        self.delivered.store(matches!(outcome, WakeOutcome::Woken), Ordering::Release);
+
+        Ok(outcome)
+    }
+
+    /// Start a batch of the values sent by the [`WakeGuard`] without waking up the [`mio::poll::Poll`],
+    /// which is woken up only once when the guard is dropped, if anything is sent.
+    ///
+    /// The guard is dropped on unwinding as well, so that the values sent ahead of a panic are still
+    /// woken for. Note that the other senders keep waking up the poll as usual.
+    pub fn wake_batch(&self) -> WakeGuard<'_, T> {
+        WakeGuard { sender: self, sent: 0 }
+    }
 
-        if let Some(waker) = &mut *self.waker.lock().unwrap() {
-            let _ = waker.wake();
+    /// Send a value counting it as pending, without waking up the [`mio::poll::Poll`].
+    fn enqueue(&self, t: T) -> Result<(), mpsc::SendError<T>> {
+        let size = self.size_of.as_ref().map_or(0, |size_of| size_of(&t));
+
+        // It's counted ahead of sending, so that the receiver never sees it below zero,
+        // nor misses it while closing the channel.
+        self.shared.pending.fetch_add(1, Ordering::SeqCst);
+
+        self.shared.bytes.fetch_add(size, Ordering::AcqRel);
+
+        let result = match self.shared.enter() {
+            Some(_guard) => self.tx.send(t),
+            None => Err(mpsc::SendError(t)),
+        };
+
+        if let Err(e) = result {
+            self.shared.pending.fetch_sub(1, Ordering::AcqRel);
+
+            self.shared.bytes.fetch_sub(size, Ordering::AcqRel);
+
+            return Err(e);
         }
 
         Ok(())
     }
+
+    /// Return `true` if the last value sent by this sender actually woke up the [`mio::poll::Poll`].
+    ///
+    /// It's a lightweight alternative to [`Sender::send_with_outcome`] for diagnostics.
+    pub fn last_wake_delivered(&self) -> bool {
+        self.delivered.load(Ordering::Acquire)
+    }
+
+    /// Wake up the [`mio::poll::Poll`] without sending anything, e.g. to nudge the receiver.
+    ///
+    /// The receiver finds nothing new on the event, so that it's a spurious wake to it.
+    /// It's subject to the coalescing and the pause just like the wakes of the values sent.
+    pub fn wake(&self) -> WakeOutcome {
+        self.shared.wake()
+    }
+
+    /// Try to send a value, with the same signature as [`SyncSender::try_send`].
+    ///
+    /// Since the channel is unbounded, it never fails with [`mpsc::TrySendError::Full`],
+    /// but only with [`mpsc::TrySendError::Disconnected`] if the receiver is gone.
+    pub fn try_send(&self, t: T) -> Result<(), mpsc::TrySendError<T>> {
+        self.send(t).map_err(|mpsc::SendError(t)| mpsc::TrySendError::Disconnected(t))
+    }
+
+    /// Send a value once the number of the values pending in the channel is below the `soft_limit`.
+    ///
+    /// It blocks the current thread with a short backoff meanwhile, so that an unbounded channel
+    /// can put a backpressure on the producers just like a bounded one.
+    /// Note that the check is racy, so that the limit can be exceeded by the other senders.
+    pub fn send_bounded(&self, t: T, soft_limit: usize) -> Result<(), mpsc::SendError<T>> {
+        let mut backoff = Duration::from_micros(1);
+
+        while self.pending() >= soft_limit {
+            if self.shared.is_closed() {
+                return Err(mpsc::SendError(t));
+            }
+
+            thread::sleep(backoff);
+
+            backoff = (backoff * 2).min(Self::MAX_BACKOFF);
+        }
+
+        self.send(t)
+    }
+
+    /// The longest time for [`Sender::send_bounded`] to sleep at once.
+    const MAX_BACKOFF: Duration = Duration::from_millis(1);
+
+    /// Send a value only if the number of the values pending in the channel is below the `threshold`.
+    ///
+    /// It returns `Ok(true)` if it's sent, or `Ok(false)` if it's dropped instead.
+    /// Note that the check is racy, so that the other senders may push the channel
+    /// over the `threshold` right after it.
+    pub fn send_if_below(&self, t: T, threshold: usize) -> Result<bool, mpsc::SendError<T>> {
+        if self.pending() >= threshold {
+            return Ok(false);
+        }
+
+        self.send(t)?;
+
+        Ok(true)
+    }
+
+    pub(crate) fn pending(&self) -> usize {
+        self.shared.pending.load(Ordering::Acquire)
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").field("name", &self.shared.name).finish_non_exhaustive()
+    }
 }
 
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
-        Self { waker: self.waker.clone(), tx: self.tx.clone() }
+        Self { size_of: self.size_of.clone(), ..Self::new(self.tx.clone(), self.shared.clone()) }
+    }
+}
+
+/// An error returned by [`SyncSender::send_deadline`], along with the value failed to be sent.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum SendTimeoutError<T> {
+    /// The deadline passed before there's room to send.
+    Timeout(T),
+    /// The receiver is gone.
+    Disconnected(T),
+}
+
+impl<T> SendTimeoutError<T> {
+    /// Return the value failed to be sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Timeout(t) | Self::Disconnected(t) => t,
+        }
+    }
+}
+
+impl<T> fmt::Debug for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout(_) => f.write_str("Timeout(..)"),
+            Self::Disconnected(_) => f.write_str("Disconnected(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout(_) => f.write_str("timed out waiting on send operation"),
+            Self::Disconnected(_) => f.write_str("sending on a closed channel"),
+        }
+    }
+}
+
+impl<T> std::error::Error for SendTimeoutError<T> {}
+
+/// A guard batching the values sent, started by [`Sender::wake_batch`].
+///
+/// Dropping it wakes up the [`mio::poll::Poll`] once, if anything is sent through it.
+pub struct WakeGuard<'s, T> {
+    sender: &'s Sender<T>,
+    sent: usize,
+}
+
+impl<T> WakeGuard<'_, T> {
+    /// Send a value without waking up the [`mio::poll::Poll`]. It works just like [`Sender::send`] otherwise.
+    pub fn send(&mut self, t: T) -> Result<(), mpsc::SendError<T>> {
+        self.sender.enqueue(t)?;
+
+        self.sent += 1;
+
+        Ok(())
+    }
+}
+
+impl<T> Drop for WakeGuard<'_, T> {
+    fn drop(&mut self) {
+        if self.sent > 0 {
+            let outcome = self.sender.shared.sent_many(self.sent);
+
+            self.sender.delivered.store(matches!(outcome, WakeOutcome::Woken), Ordering::Release);
+        }
+    }
+}
+
+/// The status of [`Receiver::try_recv_fast`], flattening the [`mpsc::TryRecvError`] into it.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvStatus<T> {
+    /// A value is received.
+    Message(T),
+    /// The channel is empty.
+    Empty,
+    /// The channel is disconnected with nothing left.
+    Disconnected,
+}
+
+impl<T> From<Result<T, mpsc::TryRecvError>> for RecvStatus<T> {
+    fn from(result: Result<T, mpsc::TryRecvError>) -> Self {
+        match result {
+            Ok(t) => Self::Message(t),
+            Err(mpsc::TryRecvError::Empty) => Self::Empty,
+            Err(mpsc::TryRecvError::Disconnected) => Self::Disconnected,
+        }
     }
 }
 
 /// A wrapper of the [`mpsc::SyncSender`].
+///
+/// Dropping the last one wakes up the [`mio::poll::Poll`].
 pub struct SyncSender<T> {
-    waker: Arc<Mutex<Option<Waker>>>,
-    tx: mpsc::SyncSender<T>
+    // The inner sender must be declared ahead of the shared state,
+    // so that the channel is already disconnected by the time the last one wakes up the poll.
+    tx: mpsc::SyncSender<T>,
+    shared: SenderRef,
+    bound: usize,
 }
 
 impl<T> SyncSender<T> {
     /// Try to send a value. It works just like [`mpsc::SyncSender::send`].
     /// After sending it, it's waking upthe [`mio::poll::Poll`].
     ///
+    /// If the channel is full, it wakes up the [`mio::poll::Poll`] ahead of blocking as well,
+    /// so that the receiver is nudged to drain it even if it has missed the earlier wakes.
+    ///
     /// Note that it does not return any I/O error even if it occurs
     /// when waking up the [`mio::poll::Poll`].
     pub fn send(&self, t: T) -> Result<(), mpsc::SendError<T>> {
-        self.tx.send(t)?;
+        // It's counted ahead of sending, so that the receiver never sees it below zero,
+        // nor misses it while closing the channel.
+        self.shared.pending.fetch_add(1, Ordering::SeqCst);
+
+        let result = match self.shared.enter() {
+            Some(_guard) => match self.tx.try_send(t) {
+                Ok(()) => Ok(()),
+                Err(mpsc::TrySendError::Full(t)) => {
+                    self.shared.wake();
+
+                    self.tx.send(t)
+                }
+                Err(mpsc::TrySendError::Disconnected(t)) => Err(mpsc::SendError(t)),
+            },
+            None => Err(mpsc::SendError(t)),
+        };
+
+        if let Err(e) = result {
+            self.shared.pending.fetch_sub(1, Ordering::AcqRel);
+
+            return Err(e);
+        }
+
+        self.shared.sent();
+
+        Ok(())
+    }
+
+    /// Try to send a value without blocking. It works just like [`mpsc::SyncSender::try_send`].
+    /// After sending it, it's waking up the [`mio::poll::Poll`].
+    pub fn try_send(&self, t: T) -> Result<(), mpsc::TrySendError<T>> {
+        self.shared.pending.fetch_add(1, Ordering::SeqCst);
+
+        if let Err(e) = self.try_send_open(t) {
+            self.shared.pending.fetch_sub(1, Ordering::AcqRel);
+
+            return Err(e);
+        }
+
+        self.shared.sent();
+
+        Ok(())
+    }
+
+    /// Send a value once there's room, unless the `deadline` passes first.
+    ///
+    /// Unlike a timeout, the `deadline` can be shared by several sends, e.g. to send
+    /// a few values under one budget. It wakes up the [`mio::poll::Poll`] ahead of waiting
+    /// just like [`SyncSender::send`], and again after sending it. It waits with a short backoff
+    /// driven by the clock of the channel, and returns the value back on the timeout or disconnection.
+    pub fn send_deadline(&self, t: T, deadline: Instant) -> Result<(), SendTimeoutError<T>> {
+        self.shared.pending.fetch_add(1, Ordering::SeqCst);
+
+        let mut t = t;
+
+        let mut backoff = Duration::from_micros(1);
+
+        let mut woken = false;
+
+        loop {
+            let back = match self.try_send_open(t) {
+                Ok(()) => {
+                    self.shared.sent();
+
+                    return Ok(());
+                }
+                Err(mpsc::TrySendError::Full(t)) => t,
+                Err(mpsc::TrySendError::Disconnected(t)) => {
+                    self.shared.pending.fetch_sub(1, Ordering::AcqRel);
 
-        if let Some(waker) = &mut *self.waker.lock().unwrap() {
-            let _ = waker.wake();
+                    return Err(SendTimeoutError::Disconnected(t));
+                }
+            };
+
+            if !woken {
+                self.shared.wake();
+
+                woken = true;
+            }
+
+            let remaining = deadline.saturating_duration_since(self.shared.clock.now());
+
+            if remaining.is_zero() {
+                self.shared.pending.fetch_sub(1, Ordering::AcqRel);
+
+                return Err(SendTimeoutError::Timeout(back));
+            }
+
+            thread::sleep(backoff.min(self.shared.clock.wait_slice(remaining)));
+
+            backoff = (backoff * 2).min(Sender::<T>::MAX_BACKOFF);
+
+            t = back;
+        }
+    }
+
+    /// Try to send all the values at once without blocking, only if there's room for all of them.
+    ///
+    /// Otherwise, it returns all of them back untouched. It wakes up the [`mio::poll::Poll`]
+    /// only once after sending them. The room is reserved among the batches sent by it,
+    /// but the check is still racy against the other methods sending a value. In that case,
+    /// the values failing to fit are returned, while the ones ahead of them are already sent.
+    pub fn try_send_all(&self, items: Vec<T>) -> Result<(), Vec<T>> {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let n = items.len();
+
+        if self.shared.pending.fetch_add(n, Ordering::SeqCst) + n > self.bound {
+            self.shared.pending.fetch_sub(n, Ordering::AcqRel);
+
+            return Err(items);
+        }
+
+        let mut items = items.into_iter();
+
+        while let Some(t) = items.next() {
+            if let Err(mpsc::TrySendError::Full(t) | mpsc::TrySendError::Disconnected(t)) = self.try_send_open(t) {
+                let rest: Vec<T> = std::iter::once(t).chain(items).collect();
+
+                self.shared.pending.fetch_sub(rest.len(), Ordering::AcqRel);
+
+                if rest.len() < n {
+                    self.shared.sent_many(n - rest.len());
+                }
+
+                return Err(rest);
+            }
+        }
+
+        self.shared.sent_many(n);
+
+        Ok(())
+    }
+
+    /// Return `true` if the values pending in the channel have reached its bound,
+    /// so that the next [`SyncSender::send`] is likely to block.
+    ///
+    /// It's told by the pending count rather than by probing the channel, so that it's only a hint,
+    /// which can be changed by the other senders and the receiver at any time. Note that the values
+    /// buffered inside the receiver, and the ones being sent, are counted as pending too.
+    pub fn is_full(&self) -> bool {
+        self.shared.pending.load(Ordering::Acquire) >= self.bound
+    }
+
+    /// Try to send a value unless the channel is closed by [`Receiver::drain_and_close`],
+    /// or the sender is cut off by [`Receiver::replace_sender`].
+    fn try_send_open(&self, t: T) -> Result<(), mpsc::TrySendError<T>> {
+        match self.shared.enter() {
+            Some(_guard) => self.tx.try_send(t),
+            None => Err(mpsc::TrySendError::Disconnected(t)),
+        }
+    }
+}
+
+#[cfg(feature = "sink")]
+impl<T> SyncSender<T> {
+    pub(crate) fn add_space_task(&self, task: &std::task::Waker) {
+        let mut tasks = lock(&self.shared.space_tasks);
+
+        if !tasks.iter().any(|t| t.will_wake(task)) {
+            tasks.push(task.clone());
+        }
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.shared.is_closed()
+    }
+
+    /// Reserve the room for a value in the pending count, and return `true` unless it's full.
+    pub(crate) fn reserve(&self) -> bool {
+        if self.shared.pending.fetch_add(1, Ordering::SeqCst) < self.bound {
+            return true;
+        }
+
+        self.unreserve();
+
+        false
+    }
+
+    pub(crate) fn unreserve(&self) {
+        self.shared.pending.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    /// Send a value the room is reserved for by [`SyncSender::reserve`].
+    pub(crate) fn send_reserved(&self, t: T) -> Result<(), mpsc::TrySendError<T>> {
+        if let Err(e) = self.try_send_open(t) {
+            self.unreserve();
+
+            return Err(e);
         }
 
+        self.shared.sent();
+
+        Ok(())
+    }
+}
+
+impl<T> SyncSender<T> {
+    pub(crate) fn register_space(&self, registry: &mio::Registry, token: Token) -> io::Result<()> {
+        self.shared.space.reregister(registry, token)?;
+
+        self.shared.space_registered.store(true, Ordering::Release);
+
         Ok(())
     }
+
+    pub(crate) fn deregister_space(&self) {
+        self.shared.space.clear();
+
+        self.shared.space_registered.store(false, Ordering::Release);
+    }
+}
+
+impl<T> fmt::Debug for SyncSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncSender").field("name", &self.shared.name).finish_non_exhaustive()
+    }
 }
 
 impl<T> Clone for SyncSender<T> {
     fn clone(&self) -> Self {
-        Self { waker: self.waker.clone(), tx: self.tx.clone() }
+        Self { tx: self.tx.clone(), shared: self.shared.clone(), bound: self.bound }
     }
 }
@@ -0,0 +1,74 @@
+use std::{fmt, time::{Duration, Instant}};
+
+#[cfg(feature = "test-util")]
+use std::sync::Mutex;
+
+#[cfg(feature = "test-util")]
+use crate::waker::lock;
+
+/// A source of the time for the timeouts of the channel.
+///
+/// The channel waits in the real time, but decides whether a timeout is due by the clock,
+/// so that the timeouts can be driven deterministically by the [`ManualClock`] in tests.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Return the current time.
+    fn now(&self) -> Instant;
+
+    /// Return how long to wait in the real time at most before looking at the clock again,
+    /// given the `remaining` time until a timeout.
+    fn wait_slice(&self, remaining: Duration) -> Duration {
+        remaining
+    }
+}
+
+/// The [`Clock`] following the system time, used by default.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// The [`Clock`] advanced only by hand.
+#[cfg(feature = "test-util")]
+#[derive(Debug)]
+pub struct ManualClock {
+    origin: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+#[cfg(feature = "test-util")]
+impl ManualClock {
+    /// The longest time to wait in the real time before looking at the clock again.
+    const SLICE: Duration = Duration::from_millis(1);
+
+    /// Create a clock stopped at the current time.
+    pub fn new() -> Self {
+        Self { origin: Instant::now(), elapsed: Mutex::new(Duration::ZERO) }
+    }
+
+    /// Advance the clock by the `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *lock(&self.elapsed) += duration;
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.origin + *lock(&self.elapsed)
+    }
+
+    fn wait_slice(&self, remaining: Duration) -> Duration {
+        remaining.min(Self::SLICE)
+    }
+}
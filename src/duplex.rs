@@ -0,0 +1,68 @@
+use std::{io, sync::mpsc};
+
+use mio::{event, Token};
+
+use crate::{channel, Receiver, Sender};
+
+/// Create a pair of the [`DuplexEnd`]s connected to each other.
+///
+/// The first end sends `A` and receives `B`, while the second one sends `B` and receives `A`.
+/// Each end is made of two channels, one for each direction, and can be registered
+/// with its own [`mio::poll::Poll`] to be woken up by the values sent from the other end.
+pub fn duplex<A, B>() -> (DuplexEnd<A, B>, DuplexEnd<B, A>) {
+    let (atx, arx) = channel();
+
+    let (btx, brx) = channel();
+
+    (DuplexEnd { tx: atx, rx: brx }, DuplexEnd { tx: btx, rx: arx })
+}
+
+/// An end of the duplex channel created by [`duplex`].
+///
+/// It sends `S` to the other end, and receives `R` from it.
+/// It implements the [`event::Source`] just like the [`Receiver`].
+pub struct DuplexEnd<S, R> {
+    tx: Sender<S>,
+    rx: Receiver<R>,
+}
+
+impl<S, R> DuplexEnd<S, R> {
+    /// Send a value to the other end. It works just like [`Sender::send`].
+    pub fn send(&self, t: S) -> Result<(), mpsc::SendError<S>> {
+        self.tx.send(t)
+    }
+
+    /// Try to receive a value from the other end. It works just like [`Receiver::try_recv`].
+    pub fn try_recv(&self) -> Result<R, mpsc::TryRecvError> {
+        self.rx.try_recv()
+    }
+
+    /// Return the [`mio::Interest`] of the events the end causes. It works just like [`Receiver::supported_interest`].
+    pub fn supported_interest(&self) -> mio::Interest {
+        self.rx.supported_interest()
+    }
+}
+
+impl<S, R> event::Source for DuplexEnd<S, R> {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        self.rx.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        self.rx.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        self.rx.deregister(registry)
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::{mpsc, atomic::{AtomicUsize, Ordering}};
+
+use crate::Sender;
+
+/// A policy for the [`MultiSender`] to pick a channel for each value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Pick the channels one after another.
+    RoundRobin,
+    /// Pick the channel with the fewest values pending.
+    LeastQueued,
+}
+
+/// A sender distributing values across several channels.
+///
+/// Each value goes to exactly one of the channels, picked by the [`Policy`],
+/// so that it can balance the load across the workers owning the receivers.
+/// Only the [`mio::poll::Poll`] of the picked channel is woken up.
+pub struct MultiSender<T> {
+    txs: Vec<Sender<T>>,
+    policy: Policy,
+    next: AtomicUsize,
+}
+
+impl<T> MultiSender<T> {
+    /// Create a [`MultiSender`] distributing values across the given senders.
+    pub fn new(txs: Vec<Sender<T>>, policy: Policy) -> Self {
+        Self { txs, policy, next: AtomicUsize::new(0) }
+    }
+
+    /// Send a value to one of the channels picked by the [`Policy`].
+    ///
+    /// If the picked channel is disconnected, it falls back to the next one in order.
+    /// It returns the value back only if all of them are disconnected.
+    pub fn send(&self, t: T) -> Result<(), mpsc::SendError<T>> {
+        if self.txs.is_empty() {
+            return Err(mpsc::SendError(t));
+        }
+
+        let len = self.txs.len();
+
+        // The counter wraps around, so that it's taken modulo ahead of the offsets added to it.
+        let start = match self.policy {
+            Policy::RoundRobin => self.next.fetch_add(1, Ordering::Relaxed) % len,
+            Policy::LeastQueued => (0..len)
+                .min_by_key(|&i| self.txs[i].pending())
+                .unwrap_or_default(),
+        };
+
+        let mut t = t;
+
+        for i in 0..len {
+            match self.txs[(start + i) % len].send(t) {
+                Ok(()) => return Ok(()),
+                Err(mpsc::SendError(back)) => t = back,
+            }
+        }
+
+        Err(mpsc::SendError(t))
+    }
+
+    /// Return the number of the channels.
+    pub fn len(&self) -> usize {
+        self.txs.len()
+    }
+
+    /// Return `true` if there is no channel to send to.
+    pub fn is_empty(&self) -> bool {
+        self.txs.is_empty()
+    }
+}
+
+impl<T> FromIterator<Sender<T>> for MultiSender<T> {
+    /// Collect the senders into a [`MultiSender`] with the [`Policy::RoundRobin`].
+    fn from_iter<I: IntoIterator<Item = Sender<T>>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect(), Policy::RoundRobin)
+    }
+}
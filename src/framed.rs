@@ -0,0 +1,57 @@
+use std::io::{self, Read, Write};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::Receiver;
+
+impl<T: Serialize> Receiver<T> {
+    /// Drain the values from the channel into the writer as length-delimited frames.
+    ///
+    /// Each frame is the length of the value serialized by [`bincode`] as a big-endian `u32`,
+    /// followed by the serialized value, which can be read back by [`read_frame`].
+    /// It returns the number of the frames written.
+    ///
+    /// A value failing to be serialized or written is put back in front of the channel, so that
+    /// nothing is lost on the error, and it's received first once again. Note that its frame may be
+    /// written partially on the failure of the writer, as well as that a value never serializable
+    /// keeps failing the drain until it's received otherwise.
+    pub fn drain_to_writer<W: Write>(&self, w: &mut W) -> io::Result<usize> {
+        let mut count = 0;
+
+        while let Ok(t) = self.try_recv() {
+            if let Err(e) = write_frame(w, &t) {
+                self.put_back(t);
+
+                return Err(e);
+            }
+
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
+/// Write the value `t` as a length-delimited frame.
+fn write_frame<T: Serialize, W: Write>(w: &mut W, t: &T) -> io::Result<()> {
+    let bytes = bincode::serialize(t).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let len = u32::try_from(bytes.len()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    w.write_all(&len.to_be_bytes())?;
+
+    w.write_all(&bytes)
+}
+
+/// Read a value from a frame written by [`Receiver::drain_to_writer`].
+pub fn read_frame<T: DeserializeOwned, R: Read>(r: &mut R) -> io::Result<T> {
+    let mut len = [0; 4];
+
+    r.read_exact(&mut len)?;
+
+    let mut bytes = vec![0; u32::from_be_bytes(len) as usize];
+
+    r.read_exact(&mut bytes)?;
+
+    bincode::deserialize(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,139 @@
+use std::{collections::{HashMap, VecDeque}, hash::Hash, io, sync::{mpsc::{self, TryRecvError}, Arc, Mutex}};
+
+use mio::{event, Token};
+
+use crate::{state::{state_channel, StateReceiver, StateSender, Values}, waker::lock};
+
+/// Create a pair of the [`KeyedSender`] and the [`KeyedReceiver`] of a channel
+/// which keeps only the newest value for each key.
+///
+/// It works just like [`crate::latest_channel`], but for each key given by `key_of`.
+/// A value sent replaces the previous one of the same key if it's not received yet,
+/// while keeping its place in the channel.
+pub fn keyed_latest_channel<K, T, F>(key_of: F) -> (KeyedSender<K, T>, KeyedReceiver<K, T>)
+where
+    K: Eq + Hash + Clone,
+    F: Fn(&T) -> K + Send + Sync + 'static,
+{
+    let (tx, rx) = state_channel(Keyed { slots: Mutex::new(Slots { values: HashMap::new(), order: VecDeque::new() }) });
+
+    (KeyedSender { shared: tx, key_of: Arc::new(key_of) }, KeyedReceiver { shared: rx })
+}
+
+struct Keyed<K, T> {
+    slots: Mutex<Slots<K, T>>,
+}
+
+impl<K, T> Values for Keyed<K, T> {
+    fn is_empty(&self) -> bool {
+        lock(&self.slots).order.is_empty()
+    }
+}
+
+/// The newest values by the keys, along with the keys in the order they're first sent.
+struct Slots<K, T> {
+    values: HashMap<K, T>,
+    order: VecDeque<K>,
+}
+
+/// The sending half of the [`keyed_latest_channel`].
+pub struct KeyedSender<K, T> {
+    shared: StateSender<Keyed<K, T>>,
+    key_of: Arc<dyn Fn(&T) -> K + Send + Sync>,
+}
+
+impl<K: Eq + Hash + Clone, T> KeyedSender<K, T> {
+    /// Replace the value of the same key with `t` and wake up the [`mio::poll::Poll`].
+    ///
+    /// The poll is woken up only if there's no value left undelivered,
+    /// since the pending event already covers the rest of them.
+    pub fn send(&self, t: T) -> Result<(), mpsc::SendError<T>> {
+        if self.shared.is_closed() {
+            return Err(mpsc::SendError(t));
+        }
+
+        let key = (self.key_of)(&t);
+
+        let mut slots = lock(&self.shared.slots);
+
+        let was_empty = slots.order.is_empty();
+
+        if slots.values.insert(key.clone(), t).is_none() {
+            slots.order.push_back(key);
+        }
+
+        drop(slots);
+
+        if was_empty {
+            self.shared.wake();
+        }
+
+        Ok(())
+    }
+}
+
+impl<K, T> Clone for KeyedSender<K, T> {
+    fn clone(&self) -> Self {
+        Self { shared: self.shared.clone(), key_of: self.key_of.clone() }
+    }
+}
+
+/// The receiving half of the [`keyed_latest_channel`], which can be registered with the [`mio::poll::Poll`].
+///
+/// It ignores the [`mio::Interest`] and always cause readable events.
+pub struct KeyedReceiver<K, T> {
+    shared: StateReceiver<Keyed<K, T>>,
+}
+
+impl<K: Eq + Hash, T> KeyedReceiver<K, T> {
+    /// Take the newest value of the key sent first among the ones left undelivered.
+    ///
+    /// It fails with [`TryRecvError::Disconnected`] only after all the values left are taken.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut slots = lock(&self.shared.slots);
+
+        if let Some(key) = slots.order.pop_front() {
+            if let Some(t) = slots.values.remove(&key) {
+                return Ok(t);
+            }
+        }
+
+        drop(slots);
+
+        Err(self.shared.nothing_left())
+    }
+
+    /// Return the number of the keys with a value left undelivered.
+    pub fn len(&self) -> usize {
+        lock(&self.shared.slots).order.len()
+    }
+
+    /// Return `true` if there is no value left undelivered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K, T> event::Source for KeyedReceiver<K, T> {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        self.shared.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        self.shared.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        self.shared.deregister(registry)
+    }
+}
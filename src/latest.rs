@@ -0,0 +1,102 @@
+use std::{io, sync::{mpsc::{self, TryRecvError}, Mutex}};
+
+use mio::{event, Token};
+
+use crate::{state::{state_channel, StateReceiver, StateSender, Values}, waker::lock};
+
+/// Create a pair of the [`LatestSender`] and the [`LatestReceiver`] of a channel
+/// which keeps only the newest value.
+///
+/// Unlike [`channel`](crate::channel), a value sent replaces the previous one
+/// if it's not received yet, so that the receiver always gets the most recent.
+pub fn latest_channel<T>() -> (LatestSender<T>, LatestReceiver<T>) {
+    let (tx, rx) = state_channel(Latest { value: Mutex::new(None) });
+
+    (LatestSender { shared: tx }, LatestReceiver { shared: rx })
+}
+
+struct Latest<T> {
+    value: Mutex<Option<T>>,
+}
+
+impl<T> Values for Latest<T> {
+    fn is_empty(&self) -> bool {
+        lock(&self.value).is_none()
+    }
+}
+
+/// The sending half of the [`latest_channel`].
+pub struct LatestSender<T> {
+    shared: StateSender<Latest<T>>,
+}
+
+impl<T> LatestSender<T> {
+    /// Replace the value with `t` and wake up the [`mio::poll::Poll`].
+    ///
+    /// The poll is woken up only if there's no value left undelivered,
+    /// since the pending event already covers the replacement.
+    pub fn send(&self, t: T) -> Result<(), mpsc::SendError<T>> {
+        if self.shared.is_closed() {
+            return Err(mpsc::SendError(t));
+        }
+
+        let previous = lock(&self.shared.value).replace(t);
+
+        if previous.is_none() {
+            self.shared.wake();
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Clone for LatestSender<T> {
+    fn clone(&self) -> Self {
+        Self { shared: self.shared.clone() }
+    }
+}
+
+/// The receiving half of the [`latest_channel`], which can be registered with the [`mio::poll::Poll`].
+///
+/// It ignores the [`mio::Interest`] and always cause readable events.
+pub struct LatestReceiver<T> {
+    shared: StateReceiver<Latest<T>>,
+}
+
+impl<T> LatestReceiver<T> {
+    /// Take the newest value, leaving the slot empty.
+    ///
+    /// It fails with [`TryRecvError::Disconnected`] only after the value left is taken.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        lock(&self.shared.value).take().ok_or_else(|| self.shared.nothing_left())
+    }
+
+    /// Return the [`mio::Interest`] of the events the receiver causes, which is always readable.
+    pub fn supported_interest(&self) -> mio::Interest {
+        mio::Interest::READABLE
+    }
+}
+
+impl<T> event::Source for LatestReceiver<T> {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        self.shared.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        self.shared.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        self.shared.deregister(registry)
+    }
+}
@@ -36,6 +36,73 @@
 //! }
 //! ```
 
+// The example above is written as a test module on purpose.
+#![allow(clippy::test_attr_in_doctest)]
+
+mod any;
+mod bridge;
+mod builder;
+mod call;
 mod channel;
+mod clock;
+mod duplex;
+mod fanout;
+mod keyed;
+mod latest;
+mod lines;
+mod metrics;
+#[cfg(feature = "framed")]
+mod framed;
+#[cfg(feature = "tokio")]
+mod pipe;
+mod polling;
+mod pool;
+mod pooled;
+mod priority;
+mod ring;
+mod router;
+mod shared;
+#[cfg(feature = "sink")]
+mod sink;
+mod state;
+#[cfg(feature = "stream")]
+mod stream;
+mod ticker;
+mod timed;
+mod token;
+mod waker;
+mod work;
+mod writable;
 
-pub use channel::{channel, sync_channel, Sender, SyncSender, Receiver};
+pub use channel::{channel, channel_deferred, channel_eager, channel_named, channel_with_heartbeat, channel_with_idle_timeout, register_all, sized_channel, sync_channel, Sender, SyncSender, Receiver, ReceiverGuard, ReceiverWaker, RecvGuard, RecvStatus, SendTimeoutError, TriggerMode, WakeGuard, WakeOutcome};
+pub use any::AnyReceiver;
+pub use bridge::{MioBridge, BridgedReceiver, RecvAsync};
+pub use builder::ChannelBuilder;
+pub use call::Call;
+#[cfg(feature = "test-util")]
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use duplex::{duplex, DuplexEnd};
+pub use fanout::{MultiSender, Policy};
+pub use keyed::{keyed_latest_channel, KeyedSender, KeyedReceiver};
+pub use latest::{latest_channel, LatestSender, LatestReceiver};
+#[cfg(feature = "framed")]
+pub use framed::read_frame;
+pub use metrics::{MetricsRegistry, MetricsSnapshot};
+#[cfg(feature = "tokio")]
+pub use pipe::Pipe;
+pub use polling::{recv_zip, Ready, RecvTiming};
+pub use pool::WakerPool;
+pub use pooled::{SenderPool, PooledSender};
+pub use priority::{priority_channel, PrioritySender, PriorityReceiver};
+pub use ring::{ring_channel, RingSender, RingReceiver};
+pub use router::Router;
+pub use shared::SharedReceiver;
+#[cfg(feature = "sink")]
+pub use sink::SyncSink;
+#[cfg(feature = "stream")]
+pub use stream::BatchStream;
+pub use timed::{timed_channel, timed_channel_with_buckets, Histogram, TimedSender, TimedReceiver};
+pub use token::{ChannelToken, SharedTokenSet};
+pub use waker::Waker;
+pub use work::{work_channel, WorkSender, WorkReceiver};
+pub use writable::{writable_channel, WritableSender};
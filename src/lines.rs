@@ -0,0 +1,34 @@
+use std::io::{self, Write};
+
+use crate::Receiver;
+
+impl Receiver<String> {
+    /// Drain the lines from the channel into the writer, each followed by `\n`.
+    ///
+    /// It returns the number of the lines written, and flushes the writer once at the end.
+    ///
+    /// A line failing to be written is put back into the channel to be received first next time,
+    /// so that it's never lost. Note that part of it may have been written already,
+    /// so wrap the writer in an [`io::BufWriter`] to retry from the start of the line.
+    pub fn drain_as_lines<W: Write>(&self, w: &mut W) -> io::Result<usize> {
+        let mut count = 0;
+
+        while let Ok(mut line) = self.try_recv() {
+            line.push('\n');
+
+            if let Err(e) = w.write_all(line.as_bytes()) {
+                line.pop();
+
+                self.put_back(line);
+
+                return Err(e);
+            }
+
+            count += 1;
+        }
+
+        w.flush()?;
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,91 @@
+use std::{fmt, sync::{Arc, Mutex, Weak, atomic::{AtomicUsize, Ordering}}};
+
+use crate::{waker::lock, WakeOutcome};
+
+/// A registry gathering the metrics of several channels, each tagged by its name.
+///
+/// It's created once and given to [`crate::ChannelBuilder::metrics_registry`],
+/// so that every channel built by it reports into the registry. The clones share the same registry.
+/// A channel is removed from the registry once it's dropped, so that it never grows unbounded
+/// by the channels created on the fly, e.g. one per connection.
+#[derive(Default, Clone)]
+pub struct MetricsRegistry {
+    channels: Arc<Mutex<Vec<Entry>>>,
+}
+
+/// The metrics of a channel tagged by its name, which are owned by the channel.
+type Entry = (String, Weak<Metrics>);
+
+impl MetricsRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take a snapshot of the metrics of all the channels alive, in the order they're created.
+    pub fn snapshot(&self) -> Vec<(String, MetricsSnapshot)> {
+        let mut channels = lock(&self.channels);
+
+        channels.retain(|(_, metrics)| metrics.strong_count() > 0);
+
+        channels.iter().filter_map(|(name, metrics)| Some((name.clone(), metrics.upgrade()?.snapshot()))).collect()
+    }
+
+    pub(crate) fn register(&self, name: String) -> Arc<Metrics> {
+        let metrics = Arc::new(Metrics::default());
+
+        let mut channels = lock(&self.channels);
+
+        channels.retain(|(_, metrics)| metrics.strong_count() > 0);
+
+        channels.push((name, Arc::downgrade(&metrics)));
+
+        metrics
+    }
+}
+
+impl fmt::Debug for MetricsRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MetricsRegistry").field("channels", &lock(&self.channels).len()).finish()
+    }
+}
+
+/// The counters of a channel reporting into a [`MetricsRegistry`].
+#[derive(Default)]
+pub(crate) struct Metrics {
+    sent: AtomicUsize,
+    woken: AtomicUsize,
+    coalesced: AtomicUsize,
+}
+
+impl Metrics {
+    /// Count the `n` values sent along with what happened to their wake.
+    pub(crate) fn sent(&self, n: usize, outcome: &WakeOutcome) {
+        self.sent.fetch_add(n, Ordering::Relaxed);
+
+        match outcome {
+            WakeOutcome::Woken => self.woken.fetch_add(1, Ordering::Relaxed),
+            WakeOutcome::Coalesced => self.coalesced.fetch_add(1, Ordering::Relaxed),
+            WakeOutcome::NoWaker | WakeOutcome::Failed(_) => 0,
+        };
+    }
+
+    fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            sent: self.sent.load(Ordering::Relaxed),
+            woken: self.woken.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A snapshot of the metrics of a channel taken by [`MetricsRegistry::snapshot`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// The number of the values sent.
+    pub sent: usize,
+    /// The number of the values which woke up the [`mio::poll::Poll`].
+    pub woken: usize,
+    /// The number of the values whose wakes are coalesced.
+    pub coalesced: usize,
+}
@@ -0,0 +1,60 @@
+use std::sync::mpsc;
+
+use tokio::sync::mpsc::{error::TrySendError, Sender};
+
+use crate::Receiver;
+
+/// What stopped [`Receiver::pipe_to_tokio`], along with the number of the values forwarded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pipe {
+    /// The channel is drained.
+    Drained(usize),
+    /// The Tokio channel is full, and the values left are kept in the receiver.
+    ///
+    /// Nothing tells when the Tokio channel has room again, so that the caller must retry
+    /// on its next poll tick, as described in [`Receiver::pipe_to_tokio`].
+    Full(usize),
+    /// The Tokio channel is closed, and the values left are kept in the receiver.
+    Closed(usize),
+    /// The channel is disconnected with nothing left.
+    Disconnected(usize),
+}
+
+impl<T> Receiver<T> {
+    /// Forward all the values available into the Tokio channel of `tx`, so that they're consumed
+    /// by the asynchronous tasks downstream. It's meant to be called on every event of the channel.
+    ///
+    /// It never blocks, but stops draining once the Tokio channel is full, putting the value back
+    /// into the receiver to be forwarded first by the next call.
+    ///
+    /// No event is caused for the values left, neither by the receiver nor when the Tokio channel
+    /// has room again, since a receive on the Tokio side can't wake up the [`mio::poll::Poll`].
+    /// So after [`Pipe::Full`], the caller must retry on its next poll tick: poll with a timeout
+    /// bounding the latency it tolerates rather than none, and call it again on every return
+    /// of the poll, including the timeout, until it returns anything but [`Pipe::Full`].
+    pub fn pipe_to_tokio(&self, tx: &Sender<T>) -> Pipe {
+        let mut forwarded = 0;
+
+        loop {
+            let t = match self.try_recv() {
+                Ok(t) => t,
+                Err(mpsc::TryRecvError::Empty) => return Pipe::Drained(forwarded),
+                Err(mpsc::TryRecvError::Disconnected) => return Pipe::Disconnected(forwarded),
+            };
+
+            match tx.try_send(t) {
+                Ok(()) => forwarded += 1,
+                Err(TrySendError::Full(t)) => {
+                    self.put_back(t);
+
+                    return Pipe::Full(forwarded);
+                }
+                Err(TrySendError::Closed(t)) => {
+                    self.put_back(t);
+
+                    return Pipe::Closed(forwarded);
+                }
+            }
+        }
+    }
+}
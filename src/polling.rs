@@ -0,0 +1,369 @@
+use std::{io, time::{Duration, Instant}, sync::mpsc};
+
+use mio::{Events, Poll, Token};
+
+use crate::Receiver;
+
+/// What is ready after [`Receiver::recv_or_event`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum Ready<T> {
+    /// The channel is woken up with a value.
+    Message(T),
+    /// Another source registered with the [`Poll`] is woken up with the token.
+    Other(Token),
+    /// The channel is woken up, but disconnected with nothing left.
+    Disconnected,
+    /// Nothing is woken up in time.
+    Timeout,
+}
+
+/// How long [`Receiver::recv_timed`] spent in each phase.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RecvTiming {
+    /// The time spent waiting in [`Poll::poll`].
+    pub wait: Duration,
+    /// The time spent receiving from the channel.
+    pub drain: Duration,
+}
+
+impl<T> Receiver<T> {
+    /// Poll the [`Poll`] once, and receive a value if the `token` is found among the `events`,
+    /// along with the time spent in each phase, so that the latency of a loop can be attributed.
+    ///
+    /// The receiver must be registered with the `poll` by the `token`. Unlike
+    /// [`Receiver::recv_or_event`], it never polls again, so that it returns `None`
+    /// on the `timeout` as well as on a spurious wake.
+    pub fn recv_timed(&self, poll: &mut Poll, events: &mut Events, token: Token, timeout: Option<Duration>) -> io::Result<(Option<T>, RecvTiming)> {
+        let started = Instant::now();
+
+        poll.poll(events, timeout)?;
+
+        let wait = started.elapsed();
+
+        let started = Instant::now();
+
+        let t = match events.iter().any(|event| event.token() == token) {
+            true => self.try_recv().ok(),
+            false => None,
+        };
+
+        Ok((t, RecvTiming { wait, drain: started.elapsed() }))
+    }
+
+    /// Poll the [`Poll`] once, and return what is ready.
+    ///
+    /// The receiver must be registered with the `poll` by the `token`. A value is received
+    /// only if the `token` is found among the `events`, in preference to the other tokens.
+    /// Only one value is received at a time, so that the rest of them should be received
+    /// by [`Receiver::try_recv`] before polling again. The `events` are left as they are,
+    /// so that all the other tokens can still be inspected afterwards.
+    ///
+    /// If the channel is woken up with nothing, it polls again within the `timeout`.
+    pub fn recv_or_event(&self, poll: &mut Poll, events: &mut Events, token: Token, timeout: Option<Duration>) -> io::Result<Ready<T>> {
+        let deadline = timeout.map(|timeout| self.clock.now() + timeout);
+
+        loop {
+            let remaining = deadline.map(|deadline| deadline.saturating_duration_since(self.clock.now()));
+
+            poll.poll(events, remaining.map(|remaining| self.clock.wait_slice(remaining)))?;
+
+            if events.is_empty() {
+                if deadline.is_some_and(|deadline| self.clock.now() >= deadline) {
+                    return Ok(Ready::Timeout);
+                }
+
+                continue;
+            }
+
+            let mut other = None;
+
+            for event in events.iter() {
+                if event.token() != token {
+                    other = other.or(Some(event.token()));
+
+                    continue;
+                }
+
+                match self.try_recv() {
+                    Ok(t) => return Ok(Ready::Message(t)),
+                    Err(mpsc::TryRecvError::Disconnected) => return Ok(Ready::Disconnected),
+                    Err(mpsc::TryRecvError::Empty) => {}
+                }
+            }
+
+            if let Some(token) = other {
+                return Ok(Ready::Other(token));
+            }
+        }
+    }
+
+    /// Poll the [`Poll`] until the nearest of the `deadlines`, and return what is ready.
+    ///
+    /// It works just like [`Receiver::recv_or_event`] with the timeout up to the nearest deadline,
+    /// which is zero if it's already past, or none if there's no deadline at all.
+    /// So [`Ready::Timeout`] means that the nearest deadline has expired.
+    pub fn poll_timeout_auto(&self, poll: &mut Poll, events: &mut Events, token: Token, deadlines: &[Instant]) -> io::Result<Ready<T>> {
+        let timeout = deadlines.iter().min().map(|deadline| deadline.saturating_duration_since(self.clock.now()));
+
+        self.recv_or_event(poll, events, token, timeout)
+    }
+
+    /// Receive a value if the token of the receiver is found among the `events` already polled.
+    ///
+    /// It returns `None` if the receiver is not registered, if its token is not found,
+    /// or if the event is spurious. Only one value is received at a time, just like
+    /// [`Receiver::recv_or_event`].
+    pub fn recv_if_ready(&self, events: &Events) -> Option<T> {
+        let token = self.token()?;
+
+        if !events.iter().any(|event| event.token() == token) {
+            return None;
+        }
+
+        self.try_recv().ok()
+    }
+
+    /// Wait for a value until the `timeout` by polling the [`Poll`] instead of blocking on the channel.
+    ///
+    /// The receiver must be registered with the `poll` by the `token`, so that the wait can be
+    /// unified with the [`Poll`] rather than relying on another mechanism. It polls again
+    /// on a spurious wake within the `timeout`. It returns `None` on the timeout,
+    /// or if the channel is disconnected with nothing left.
+    ///
+    /// It also returns `None` early once an event of another token is polled, leaving all the events
+    /// polled in the `events` for the caller to handle, since an edge-triggered readiness is never
+    /// reported again. So the `events` are empty on the timeout, and they're overwritten on every call.
+    pub fn recv_timeout_polled(&self, poll: &mut Poll, events: &mut Events, token: Token, timeout: Duration) -> io::Result<Option<T>> {
+        let deadline = self.clock.now() + timeout;
+
+        loop {
+            match self.try_recv() {
+                Ok(t) => return Ok(Some(t)),
+                Err(mpsc::TryRecvError::Disconnected) => return Ok(None),
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+
+            if !self.wait(poll, events, token, Some(deadline))? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Wait for the first value satisfying the predicate `pred` by polling the [`Poll`].
+    ///
+    /// The receiver must be registered with the `poll` by the `token`. The values failing
+    /// the predicate are received and dropped for good. It returns `None` on the `timeout`,
+    /// or if the channel is disconnected with nothing left. It returns `None` early on an event
+    /// of another token as well, leaving it in the `events`, just like [`Receiver::recv_timeout_polled`].
+    pub fn wait_for<F: FnMut(&T) -> bool>(&self, poll: &mut Poll, events: &mut Events, token: Token, timeout: Option<Duration>, mut pred: F) -> io::Result<Option<T>> {
+        let deadline = timeout.map(|timeout| self.clock.now() + timeout);
+
+        loop {
+            loop {
+                match self.try_recv() {
+                    Ok(t) if pred(&t) => return Ok(Some(t)),
+                    Ok(_) => {}
+                    Err(mpsc::TryRecvError::Disconnected) => return Ok(None),
+                    Err(mpsc::TryRecvError::Empty) => break,
+                }
+            }
+
+            if !self.wait(poll, events, token, deadline)? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Wait for exactly `n` values by polling the [`Poll`].
+    ///
+    /// The receiver must be registered with the `poll` by the `token`. The `timeout` is
+    /// the budget for all of them, rather than for each one. It returns the values
+    /// collected so far as an `Err` on the `timeout`, or if the channel is disconnected
+    /// before collecting all of them. They're returned as an `Err` early on an event of another token
+    /// as well, leaving it in the `events`, just like [`Receiver::recv_timeout_polled`].
+    pub fn recv_exact(&self, n: usize, poll: &mut Poll, events: &mut Events, token: Token, timeout: Option<Duration>) -> io::Result<Result<Vec<T>, Vec<T>>> {
+        let deadline = timeout.map(|timeout| self.clock.now() + timeout);
+
+        let mut batch = Vec::with_capacity(n);
+
+        loop {
+            while batch.len() < n {
+                match self.try_recv() {
+                    Ok(t) => batch.push(t),
+                    Err(mpsc::TryRecvError::Disconnected) => return Ok(Err(batch)),
+                    Err(mpsc::TryRecvError::Empty) => break,
+                }
+            }
+
+            if batch.len() == n {
+                self.batch_received();
+
+                return Ok(Ok(batch));
+            }
+
+            if !self.wait(poll, events, token, deadline)? {
+                return Ok(Err(batch));
+            }
+        }
+    }
+
+    /// Wait for a group of the values terminated by a marker by polling the [`Poll`].
+    ///
+    /// The receiver must be registered with the `poll` by the `token`. The group is made of
+    /// the values ahead of the first one satisfying `is_marker`, exclusive of the marker,
+    /// which is received and dropped. It returns `None` on the `timeout`, or if the channel
+    /// is disconnected before the marker, leaving the values collected so far in the receiver,
+    /// so that they're received first by the next call. It returns `None` in the same way early
+    /// on an event of another token, leaving it in the `events`, just like [`Receiver::recv_timeout_polled`].
+    pub fn recv_group<F: Fn(&T) -> bool>(&self, is_marker: F, poll: &mut Poll, events: &mut Events, token: Token, timeout: Option<Duration>) -> io::Result<Option<Vec<T>>> {
+        let deadline = timeout.map(|timeout| self.clock.now() + timeout);
+
+        let mut group = Vec::new();
+
+        loop {
+            let disconnected = loop {
+                match self.try_recv() {
+                    Ok(t) if is_marker(&t) => {
+                        self.batch_received();
+
+                        return Ok(Some(group));
+                    }
+                    Ok(t) => group.push(t),
+                    Err(mpsc::TryRecvError::Disconnected) => break true,
+                    Err(mpsc::TryRecvError::Empty) => break false,
+                }
+            };
+
+            if disconnected || !self.wait(poll, events, token, deadline)? {
+                for t in group.into_iter().rev() {
+                    self.put_back(t);
+                }
+
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Wait for a value by polling the [`Poll`], tolerating up to `max_spurious` spurious wakes,
+    /// i.e. the events of the `token` finding nothing to receive.
+    ///
+    /// The receiver must be registered with the `poll` by the `token`. It returns the value
+    /// as soon as one is available, or `None` on the `timeout` or the disconnection.
+    /// It fails with [`io::ErrorKind::WouldBlock`] once the spurious wakes exceed the budget,
+    /// so that the caller never loops over them unboundedly. It returns `None` early on an event
+    /// of another token, leaving it in the `events`, just like [`Receiver::recv_timeout_polled`].
+    pub fn recv_retry_on_spurious(&self, poll: &mut Poll, events: &mut Events, token: Token, max_spurious: usize, timeout: Option<Duration>) -> io::Result<Option<T>> {
+        let deadline = timeout.map(|timeout| self.clock.now() + timeout);
+
+        let mut spurious = None;
+
+        loop {
+            match self.try_recv() {
+                Ok(t) => return Ok(Some(t)),
+                Err(mpsc::TryRecvError::Disconnected) => return Ok(None),
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+
+            // Nothing is found on the wake, unless it's the first look ahead of polling.
+            if let Some(spurious) = &mut spurious {
+                if *spurious == max_spurious {
+                    return Err(io::Error::new(io::ErrorKind::WouldBlock, "too many spurious wakes"));
+                }
+
+                *spurious += 1;
+            }
+
+            if !self.wait(poll, events, token, deadline)? {
+                return Ok(None);
+            }
+
+            spurious.get_or_insert(0);
+        }
+    }
+
+    /// Poll the [`Poll`] until the `token` is found among the `events`.
+    ///
+    /// It returns `false` if the `deadline` passes first, or if any other token is found.
+    pub(crate) fn wait(&self, poll: &mut Poll, events: &mut Events, token: Token, deadline: Option<Instant>) -> io::Result<bool> {
+        self.wait_any(poll, events, &[token], deadline)
+    }
+
+    /// Poll the [`Poll`] until any of the `tokens` is found among the `events`.
+    ///
+    /// It returns `false` if the `deadline` passes first, leaving the `events` empty,
+    /// or as soon as any other token is found, leaving the `events` for the caller.
+    fn wait_any(&self, poll: &mut Poll, events: &mut Events, tokens: &[Token], deadline: Option<Instant>) -> io::Result<bool> {
+        loop {
+            let remaining = deadline.map(|deadline| deadline.saturating_duration_since(self.clock.now()));
+
+            if remaining.is_some_and(|remaining| remaining.is_zero()) {
+                events.clear();
+
+                return Ok(false);
+            }
+
+            poll.poll(events, remaining.map(|remaining| self.clock.wait_slice(remaining)))?;
+
+            if events.iter().any(|event| !tokens.contains(&event.token())) {
+                return Ok(false);
+            }
+
+            if !events.is_empty() {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+/// Wait for a value from each of the two receivers by polling the [`Poll`], and return them as a pair.
+///
+/// The receivers must be registered with the `poll` by the `tokens` respectively. A value received
+/// from one of them is held while waiting for the other. It returns `None` on the `timeout`,
+/// or if either channel is disconnected first, leaving the value held in its receiver,
+/// so that it's received first by the next call. It returns `None` in the same way early
+/// on an event of another token, leaving it in the `events`, just like [`Receiver::recv_timeout_polled`].
+pub fn recv_zip<A, B>(a: &Receiver<A>, b: &Receiver<B>, poll: &mut Poll, events: &mut Events, tokens: (Token, Token), timeout: Option<Duration>) -> io::Result<Option<(A, B)>> {
+    let deadline = timeout.map(|timeout| a.clock.now() + timeout);
+
+    let mut left = None;
+
+    let mut right = None;
+
+    loop {
+        let mut disconnected = false;
+
+        if left.is_none() {
+            match a.try_recv() {
+                Ok(t) => left = Some(t),
+                Err(mpsc::TryRecvError::Disconnected) => disconnected = true,
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+
+        if right.is_none() {
+            match b.try_recv() {
+                Ok(t) => right = Some(t),
+                Err(mpsc::TryRecvError::Disconnected) => disconnected = true,
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+
+        if let (Some(_), Some(_)) = (&left, &right) {
+            return Ok(left.zip(right));
+        }
+
+        let woken = !disconnected && a.wait_any(poll, events, &[tokens.0, tokens.1], deadline)?;
+
+        if !woken {
+            if let Some(t) = left {
+                a.put_back(t);
+            }
+
+            if let Some(t) = right {
+                b.put_back(t);
+            }
+
+            return Ok(None);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use std::io;
+
+use mio::Token;
+
+use crate::{channel, waker::Registration, Receiver, Sender};
+
+/// A pool of the [`Waker`](crate::Waker)s, one for each node, e.g. a NUMA node or a pinned worker thread.
+///
+/// The channels created by [`WakerPool::channel_affine`] share the [`Waker`](crate::Waker) of the node
+/// hinted, so that their senders wake up the [`mio::poll::Poll`] on the same node.
+/// Since the channels on a node share the [`Token`] as well, all of their receivers
+/// should be drained on an event of it.
+#[derive(Debug, Default)]
+pub struct WakerPool {
+    wakers: Vec<Registration>,
+}
+
+impl WakerPool {
+    /// Create an empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a node woken up by the [`Waker`](crate::Waker) registered with the `registry`, and return its index.
+    pub fn add_node(&mut self, registry: &mio::Registry, token: Token) -> io::Result<usize> {
+        self.wakers.push(Registration::new(registry, token)?);
+
+        Ok(self.wakers.len() - 1)
+    }
+
+    /// Return the number of the nodes.
+    pub fn len(&self) -> usize {
+        self.wakers.len()
+    }
+
+    /// Return `true` if there is no node.
+    pub fn is_empty(&self) -> bool {
+        self.wakers.is_empty()
+    }
+
+    /// Create a pair of the [`Sender`] and the [`Receiver`] already registered
+    /// with the [`Waker`](crate::Waker) of the node hinted by `node_hint`.
+    ///
+    /// The hint is only best-effort: it's taken modulo the number of the nodes,
+    /// and the channel is left unregistered just like [`crate::channel`] if the pool is empty.
+    pub fn channel_affine<T>(&self, node_hint: usize) -> (Sender<T>, Receiver<T>) {
+        if self.wakers.is_empty() {
+            return channel();
+        }
+
+        channel::channel_with_registration(self.wakers[node_hint % self.wakers.len()].clone())
+    }
+}
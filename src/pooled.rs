@@ -0,0 +1,60 @@
+use std::sync::{mpsc, Arc, atomic::{AtomicUsize, Ordering}};
+
+use crate::Sender;
+
+/// A pool handing out up to a number of the [`PooledSender`]s of a single [`Sender`].
+///
+/// It keeps track of the handles in use, so that it can report the utilization
+/// and refuse to hand out more than the maximum.
+pub struct SenderPool<T> {
+    tx: Sender<T>,
+    max: usize,
+    in_use: Arc<AtomicUsize>,
+}
+
+impl<T> SenderPool<T> {
+    /// Create a pool of the `tx`, handing out up to `max` handles at once.
+    pub fn new(tx: Sender<T>, max: usize) -> Self {
+        Self { tx, max, in_use: Arc::new(AtomicUsize::new(0)) }
+    }
+
+    /// Hand out a handle, or nothing if `max` of them are already in use.
+    ///
+    /// The handle is returned to the pool when it's dropped.
+    pub fn acquire(&self) -> Option<PooledSender<T>> {
+        self.in_use
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < self.max).then_some(n + 1))
+            .ok()?;
+
+        Some(PooledSender { tx: self.tx.clone(), in_use: self.in_use.clone() })
+    }
+
+    /// Return the number of the handles in use.
+    pub fn in_use(&self) -> usize {
+        self.in_use.load(Ordering::Acquire)
+    }
+
+    /// Return the maximum number of the handles in use at once.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+}
+
+/// A handle handed out by the [`SenderPool`].
+pub struct PooledSender<T> {
+    tx: Sender<T>,
+    in_use: Arc<AtomicUsize>,
+}
+
+impl<T> PooledSender<T> {
+    /// Send a value. It works just like [`Sender::send`].
+    pub fn send(&self, t: T) -> Result<(), mpsc::SendError<T>> {
+        self.tx.send(t)
+    }
+}
+
+impl<T> Drop for PooledSender<T> {
+    fn drop(&mut self) {
+        self.in_use.fetch_sub(1, Ordering::AcqRel);
+    }
+}
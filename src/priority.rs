@@ -0,0 +1,97 @@
+use std::{io, sync::mpsc};
+
+use mio::{event::Source, Token};
+
+use crate::{channel, Receiver, Sender};
+
+/// Create a pair of the [`PrioritySender`] and the [`PriorityReceiver`] of a channel
+/// with two lanes, one for the normal values and the other for the priority ones.
+///
+/// Each lane wakes up the [`mio::poll::Poll`] by its own token, so that the consumer can tell
+/// the priority values from the others by the events, and service them first.
+pub fn priority_channel<T>() -> (PrioritySender<T>, PriorityReceiver<T>) {
+    let (normal_tx, normal_rx) = channel();
+
+    let (priority_tx, priority_rx) = channel();
+
+    (
+        PrioritySender { normal: normal_tx, priority: priority_tx },
+        PriorityReceiver { normal: normal_rx, priority: priority_rx },
+    )
+}
+
+/// The sending half of the [`priority_channel`].
+pub struct PrioritySender<T> {
+    normal: Sender<T>,
+    priority: Sender<T>,
+}
+
+impl<T> PrioritySender<T> {
+    /// Send a normal value, waking up the poll by the normal token. It works just like [`Sender::send`].
+    pub fn send(&self, t: T) -> Result<(), mpsc::SendError<T>> {
+        self.normal.send(t)
+    }
+
+    /// Send a priority value, waking up the poll by the priority token. It works just like [`Sender::send`].
+    pub fn send_priority(&self, t: T) -> Result<(), mpsc::SendError<T>> {
+        self.priority.send(t)
+    }
+}
+
+impl<T> Clone for PrioritySender<T> {
+    fn clone(&self) -> Self {
+        Self { normal: self.normal.clone(), priority: self.priority.clone() }
+    }
+}
+
+/// The receiving half of the [`priority_channel`].
+///
+/// Unlike the [`Receiver`], it's registered by [`PriorityReceiver::register`] with two tokens,
+/// rather than as an [`event::Source`](mio::event::Source).
+pub struct PriorityReceiver<T> {
+    normal: Receiver<T>,
+    priority: Receiver<T>,
+}
+
+impl<T> PriorityReceiver<T> {
+    /// Register the lanes with the `registry` by the `normal` and the `priority` tokens respectively.
+    pub fn register(&mut self, registry: &mio::Registry, normal: Token, priority: Token) -> io::Result<()> {
+        self.normal.register(registry, normal, mio::Interest::READABLE)?;
+
+        if let Err(e) = self.priority.register(registry, priority, mio::Interest::READABLE) {
+            self.normal.deregister(registry)?;
+
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Deregister both of the lanes.
+    pub fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        self.normal.deregister(registry)?;
+
+        self.priority.deregister(registry)
+    }
+
+    /// Try to receive a value, taking the priority ones ahead of the normal ones.
+    ///
+    /// It fails with [`mpsc::TryRecvError::Disconnected`] only after both of the lanes are drained.
+    pub fn try_recv(&self) -> Result<T, mpsc::TryRecvError> {
+        match self.priority.try_recv() {
+            Ok(t) => Ok(t),
+            Err(mpsc::TryRecvError::Empty) => self.normal.try_recv().map_err(|_| mpsc::TryRecvError::Empty),
+            Err(mpsc::TryRecvError::Disconnected) => self.normal.try_recv(),
+        }
+    }
+
+    /// Try to receive a priority value only. It works just like [`Receiver::try_recv`].
+    pub fn try_recv_priority(&self) -> Result<T, mpsc::TryRecvError> {
+        self.priority.try_recv()
+    }
+
+    /// Try to receive a normal value only. It works just like [`Receiver::try_recv`].
+    pub fn try_recv_normal(&self) -> Result<T, mpsc::TryRecvError> {
+        self.normal.try_recv()
+    }
+}
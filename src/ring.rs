@@ -0,0 +1,153 @@
+use std::{collections::VecDeque, io, sync::{mpsc::{self, TryRecvError}, Mutex}};
+
+use mio::{event, Token};
+
+use crate::{state::{state_channel, StateReceiver, StateSender, Values}, waker::lock};
+
+/// Create a pair of the [`RingSender`] and the [`RingReceiver`] of a channel
+/// which keeps at most `cap` values.
+///
+/// Unlike [`sync_channel`](crate::sync_channel), a value sent to the full channel never blocks,
+/// but evicts the oldest value instead, so that the receiver always gets the newest `cap` ones.
+///
+/// # Panics
+///
+/// It panics if `cap` is zero.
+pub fn ring_channel<T>(cap: usize) -> (RingSender<T>, RingReceiver<T>) {
+    assert!(cap > 0, "the capacity of a ring channel must be positive");
+
+    let (tx, rx) = state_channel(Ring {
+        values: Mutex::new(VecDeque::with_capacity(cap)),
+        cap,
+        on_overflow: Mutex::new(None),
+    });
+
+    (RingSender { shared: tx }, RingReceiver { shared: rx })
+}
+
+struct Ring<T> {
+    values: Mutex<VecDeque<T>>,
+    cap: usize,
+    on_overflow: Mutex<Option<Overflow<T>>>,
+}
+
+impl<T> Values for Ring<T> {
+    fn is_empty(&self) -> bool {
+        lock(&self.values).is_empty()
+    }
+}
+
+/// A callback called with each value evicted by the overflow.
+type Overflow<T> = Box<dyn FnMut(T) + Send>;
+
+/// The sending half of the [`ring_channel`].
+pub struct RingSender<T> {
+    shared: StateSender<Ring<T>>,
+}
+
+impl<T> RingSender<T> {
+    /// Push `t` into the channel, evicting the oldest value if it's full, and wake up the [`mio::poll::Poll`].
+    ///
+    /// The poll is woken up only if the channel was empty,
+    /// since the pending event already covers the values behind.
+    /// The value evicted is passed to the callback set by [`RingReceiver::on_overflow`], if any.
+    pub fn send(&self, t: T) -> Result<(), mpsc::SendError<T>> {
+        if self.shared.is_closed() {
+            return Err(mpsc::SendError(t));
+        }
+
+        let mut values = lock(&self.shared.values);
+
+        let evicted = match values.len() == self.shared.cap {
+            true => values.pop_front(),
+            false => None,
+        };
+
+        values.push_back(t);
+
+        let was_empty = evicted.is_none() && values.len() == 1;
+
+        drop(values);
+
+        if was_empty {
+            self.shared.wake();
+        }
+
+        if let Some(evicted) = evicted {
+            if let Some(f) = lock(&self.shared.on_overflow).as_mut() {
+                f(evicted);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Clone for RingSender<T> {
+    fn clone(&self) -> Self {
+        Self { shared: self.shared.clone() }
+    }
+}
+
+/// The receiving half of the [`ring_channel`], which can be registered with the [`mio::poll::Poll`].
+///
+/// It ignores the [`mio::Interest`] and always cause readable events.
+pub struct RingReceiver<T> {
+    shared: StateReceiver<Ring<T>>,
+}
+
+impl<T> RingReceiver<T> {
+    /// Take the oldest value left.
+    ///
+    /// It fails with [`TryRecvError::Disconnected`] only after all the values left are taken.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        lock(&self.shared.values).pop_front().ok_or_else(|| self.shared.nothing_left())
+    }
+
+    /// Return the number of the values left in the channel, which never exceeds the capacity.
+    pub fn len(&self) -> usize {
+        lock(&self.shared.values).len()
+    }
+
+    /// Return `true` if no value is left in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Set the callback called with each value evicted by the overflow, replacing the previous one,
+    /// so that the values lost can be counted or logged.
+    ///
+    /// It's called on the thread of the sender evicting the value, outside the lock of the values.
+    pub fn on_overflow<F: FnMut(T) + Send + 'static>(&self, f: F) {
+        *lock(&self.shared.on_overflow) = Some(Box::new(f));
+    }
+
+    /// Return the [`mio::Interest`] of the events the receiver causes, which is always readable.
+    pub fn supported_interest(&self) -> mio::Interest {
+        mio::Interest::READABLE
+    }
+}
+
+impl<T> event::Source for RingReceiver<T> {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        self.shared.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        self.shared.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        self.shared.deregister(registry)
+    }
+}
@@ -0,0 +1,75 @@
+use std::{io, cell::RefCell};
+
+use mio::{event::Source, Events, Interest, Token};
+
+use crate::Receiver;
+
+type Handler<T> = Box<dyn FnMut(T)>;
+
+/// A router dispatching the values of several [`Receiver`]s to their handlers by the [`Token`].
+///
+/// Each [`Receiver`] is registered with its own [`Token`], so that [`Router::dispatch`]
+/// drains only the receivers whose events fired.
+pub struct Router<T> {
+    routes: Vec<(Token, Receiver<T>, RefCell<Handler<T>>)>,
+}
+
+impl<T> Default for Router<T> {
+    fn default() -> Self {
+        Self { routes: Vec::new() }
+    }
+}
+
+impl<T> Router<T> {
+    /// Create a router without any routes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the `rx` with the `registry` and route its values to the `handler`.
+    pub fn route<F>(&mut self, registry: &mio::Registry, mut rx: Receiver<T>, token: Token, handler: F) -> io::Result<()>
+    where
+        F: FnMut(T) + 'static,
+    {
+        rx.register(registry, token, Interest::READABLE)?;
+
+        self.routes.push((token, rx, RefCell::new(Box::new(handler))));
+
+        Ok(())
+    }
+
+    /// Drain the receivers whose tokens are found in the `events`, invoking their handlers for each value.
+    ///
+    /// It returns the number of values dispatched.
+    pub fn dispatch(&self, events: &Events) -> usize {
+        let mut count = 0;
+
+        for event in events.iter() {
+            for (token, rx, handler) in &self.routes {
+                if *token != event.token() {
+                    continue;
+                }
+
+                let mut handler = handler.borrow_mut();
+
+                while let Ok(t) = rx.try_recv() {
+                    handler(t);
+
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Return the number of the routes.
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Return `true` if there is no route.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
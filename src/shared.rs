@@ -0,0 +1,72 @@
+use std::{io, sync::{mpsc, Arc, Mutex}};
+
+use mio::{event, Token};
+
+use crate::{waker::lock, Receiver};
+
+/// A [`Receiver`] shared by several threads.
+///
+/// The clones receive from the same channel, so that each value is received by one of them.
+/// It implements the [`event::Source`] just like the [`Receiver`].
+pub struct SharedReceiver<T> {
+    inner: Arc<Mutex<Receiver<T>>>,
+}
+
+impl<T> Receiver<T> {
+    /// Turn the receiver into a [`SharedReceiver`].
+    pub fn into_shared(self) -> SharedReceiver<T> {
+        SharedReceiver { inner: Arc::new(Mutex::new(self)) }
+    }
+}
+
+impl<T> SharedReceiver<T> {
+    /// Try to receive a value. It works just like [`Receiver::try_recv`].
+    pub fn try_recv(&self) -> Result<T, mpsc::TryRecvError> {
+        lock(&self.inner).try_recv()
+    }
+
+    /// Try to receive a batch of values at once. It works just like [`Receiver::steal`].
+    pub fn steal(&self) -> Vec<T> {
+        lock(&self.inner).steal()
+    }
+
+    /// Return the number of the values pending in the channel. It works just like [`Receiver::len`].
+    pub fn len(&self) -> usize {
+        lock(&self.inner).len()
+    }
+
+    /// Return `true` if there is no value pending in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Clone for SharedReceiver<T> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<T> event::Source for SharedReceiver<T> {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        lock(&self.inner).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        lock(&self.inner).reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        lock(&self.inner).deregister(registry)
+    }
+}
@@ -0,0 +1,80 @@
+use std::{pin::Pin, sync::mpsc, task::{Context, Poll}};
+
+use futures_sink::Sink;
+
+use crate::SyncSender;
+
+impl<T> SyncSender<T> {
+    /// Turn the sender into a [`SyncSink`].
+    pub fn into_sink(self) -> SyncSink<T> {
+        SyncSink { tx: self, reserved: false }
+    }
+}
+
+/// A [`Sink`] sending the values into a bounded channel, which is ready only when there's room.
+///
+/// The room is reserved by [`Sink::poll_ready`] in the pending count, so that it's pending
+/// while the channel is full, and woken up once the [`crate::Receiver`] receives a value.
+/// All the sinks waiting on the channel are woken up together, and race for the room.
+/// Note that a channel bounded by zero is never ready. It's ready as soon as the channel is closed,
+/// so that the next [`Sink::start_send`] fails with the value back.
+pub struct SyncSink<T> {
+    tx: SyncSender<T>,
+    reserved: bool,
+}
+
+// The values are never pinned, since they're moved into the channel right away.
+impl<T> Unpin for SyncSink<T> {}
+
+impl<T> Sink<T> for SyncSink<T> {
+    type Error = mpsc::SendError<T>;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.reserved || self.tx.is_closed() {
+            return Poll::Ready(Ok(()));
+        }
+
+        if self.tx.reserve() {
+            self.reserved = true;
+
+            return Poll::Ready(Ok(()));
+        }
+
+        self.tx.add_space_task(cx.waker());
+
+        // A value received right before setting the task may have skipped its wake,
+        // so that the room must be looked for once again.
+        if self.tx.reserve() {
+            self.reserved = true;
+
+            return Poll::Ready(Ok(()));
+        }
+
+        Poll::Pending
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, t: T) -> Result<(), Self::Error> {
+        let result = match std::mem::take(&mut self.reserved) {
+            true => self.tx.send_reserved(t),
+            false => self.tx.try_send(t),
+        };
+
+        result.map_err(|(mpsc::TrySendError::Full(t) | mpsc::TrySendError::Disconnected(t))| mpsc::SendError(t))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T> Drop for SyncSink<T> {
+    fn drop(&mut self) {
+        if self.reserved {
+            self.tx.unreserve();
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::{io, ops::Deref, sync::{mpsc::TryRecvError, Arc, atomic::{AtomicBool, AtomicUsize, Ordering}}};
+
+use mio::{event, Token};
+
+use crate::waker::WakerSlot;
+
+/// The values kept by a channel built on the [`StateSender`] and the [`StateReceiver`].
+pub(crate) trait Values {
+    /// Return `true` if no value is left undelivered.
+    fn is_empty(&self) -> bool;
+}
+
+/// The values of a channel kept in a state of its own rather than in a [`std::sync::mpsc`] channel,
+/// along with the registration and the liveness of the two halves, e.g. of the [`crate::ring_channel`].
+struct State<S> {
+    values: S,
+    waker: WakerSlot,
+    senders: AtomicUsize,
+    closed: AtomicBool,
+}
+
+/// Create a pair of the halves sharing the `values`.
+pub(crate) fn state_channel<S>(values: S) -> (StateSender<S>, StateReceiver<S>) {
+    let state = Arc::new(State {
+        values,
+        waker: WakerSlot::default(),
+        senders: AtomicUsize::new(1),
+        closed: AtomicBool::new(false),
+    });
+
+    (StateSender { state: state.clone() }, StateReceiver { state })
+}
+
+/// The sending half sharing the values, which dereferences to them.
+///
+/// The clones are counted, so that the last one dropped wakes up the [`mio::poll::Poll`]
+/// to tell the disconnection.
+pub(crate) struct StateSender<S> {
+    state: Arc<State<S>>,
+}
+
+impl<S> StateSender<S> {
+    /// Return `true` if the receiver is dropped, so that nothing should be sent anymore.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.state.closed.load(Ordering::SeqCst)
+    }
+
+    /// Wake up the [`mio::poll::Poll`] if the receiver is registered.
+    pub(crate) fn wake(&self) {
+        self.state.waker.wake();
+    }
+}
+
+impl<S> Deref for StateSender<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.state.values
+    }
+}
+
+impl<S> Clone for StateSender<S> {
+    fn clone(&self) -> Self {
+        self.state.senders.fetch_add(1, Ordering::SeqCst);
+
+        Self { state: self.state.clone() }
+    }
+}
+
+impl<S> Drop for StateSender<S> {
+    fn drop(&mut self) {
+        if self.state.senders.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.state.waker.wake();
+        }
+    }
+}
+
+/// The receiving half sharing the values, which dereferences to them.
+///
+/// It closes the channel once it's dropped. It ignores the [`mio::Interest`] and always cause readable events.
+pub(crate) struct StateReceiver<S> {
+    state: Arc<State<S>>,
+}
+
+impl<S> StateReceiver<S> {
+    /// Return the error of finding nothing to receive, which is [`TryRecvError::Disconnected`]
+    /// only after all the senders are dropped.
+    pub(crate) fn nothing_left(&self) -> TryRecvError {
+        match self.state.senders.load(Ordering::SeqCst) {
+            0 => TryRecvError::Disconnected,
+            _ => TryRecvError::Empty,
+        }
+    }
+}
+
+impl<S> Deref for StateReceiver<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.state.values
+    }
+}
+
+impl<S> Drop for StateReceiver<S> {
+    fn drop(&mut self) {
+        self.state.closed.store(true, Ordering::SeqCst);
+    }
+}
+
+impl<S: Values> event::Source for StateReceiver<S> {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        _: mio::Interest,
+    ) -> io::Result<()> {
+        if self.state.waker.register(registry, token, None)? && !self.state.values.is_empty() {
+            self.state.waker.wake();
+        }
+
+        Ok(())
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        _: mio::Interest,
+    ) -> io::Result<()> {
+        self.state.waker.reregister(registry, token)
+    }
+
+    fn deregister(&mut self, _: &mio::Registry) -> io::Result<()> {
+        self.state.waker.clear();
+
+        Ok(())
+    }
+}
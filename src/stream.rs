@@ -0,0 +1,61 @@
+use std::{pin::Pin, sync::mpsc, task::{Context, Poll}};
+
+use futures_core::Stream;
+
+use crate::Receiver;
+
+impl<T> Receiver<T> {
+    /// Turn the receiver into a [`BatchStream`].
+    pub fn into_batch_stream(self) -> BatchStream<T> {
+        BatchStream { rx: self }
+    }
+}
+
+/// A [`Stream`] yielding all the values available in the channel at once on each wake.
+///
+/// It never yields an empty batch, but waits for at least one value instead.
+/// It ends once the channel is disconnected and all the values left are yielded.
+pub struct BatchStream<T> {
+    rx: Receiver<T>,
+}
+
+impl<T> BatchStream<T> {
+    /// Drain the values available, along with `true` if the channel is found disconnected.
+    fn drain(&self) -> (Vec<T>, bool) {
+        let mut batch = Vec::new();
+
+        loop {
+            match self.rx.try_recv() {
+                Ok(t) => batch.push(t),
+                Err(mpsc::TryRecvError::Empty) => return (batch, false),
+                Err(mpsc::TryRecvError::Disconnected) => return (batch, true),
+            }
+        }
+    }
+}
+
+impl<T> Stream for BatchStream<T> {
+    type Item = Vec<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Vec<T>>> {
+        let (batch, disconnected) = self.drain();
+
+        if !batch.is_empty() {
+            return Poll::Ready(Some(batch));
+        }
+
+        if disconnected {
+            return Poll::Ready(None);
+        }
+
+        self.rx.set_task(cx.waker());
+
+        // A value sent right before setting the task may have skipped its wake,
+        // so that it must be looked for once again.
+        match self.drain() {
+            (batch, _) if !batch.is_empty() => Poll::Ready(Some(batch)),
+            (_, true) => Poll::Ready(None),
+            _ => Poll::Pending,
+        }
+    }
+}
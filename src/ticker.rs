@@ -0,0 +1,61 @@
+use std::{sync::{mpsc, Arc}, thread, time::{Duration, Instant}};
+
+use crate::clock::Clock;
+
+/// A background thread calling a function at the deadlines by the [`Clock`].
+///
+/// The thread is stopped and joined when it's dropped.
+pub(crate) struct Ticker {
+    stop: Option<mpsc::Sender<()>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl Ticker {
+    /// Call `f` at a fixed interval.
+    pub(crate) fn spawn<F: FnMut() + Send + 'static>(clock: Arc<dyn Clock>, interval: Duration, mut f: F) -> Self {
+        let first = clock.now() + interval;
+
+        Self::spawn_at(clock, first, move |deadline| {
+            f();
+
+            deadline + interval
+        })
+    }
+
+    /// Call `f` at the `first` deadline, and then at every deadline returned by it.
+    pub(crate) fn spawn_at<F: FnMut(Instant) -> Instant + Send + 'static>(clock: Arc<dyn Clock>, first: Instant, mut f: F) -> Self {
+        let (stop, stopped) = mpsc::channel();
+
+        let handle = thread::spawn(move || {
+            let mut deadline = first;
+
+            loop {
+                let remaining = deadline.saturating_duration_since(clock.now());
+
+                if remaining.is_zero() {
+                    deadline = f(deadline);
+
+                    continue;
+                }
+
+                if let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(clock.wait_slice(remaining)) {
+                    continue;
+                }
+
+                break;
+            }
+        });
+
+        Self { stop: Some(stop), handle: Some(handle) }
+    }
+}
+
+impl Drop for Ticker {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::{cell::RefCell, io, sync::{mpsc, Arc}, time::{Duration, Instant}};
+
+use mio::{event, Token};
+
+use crate::{channel, clock::Clock, Receiver, Sender};
+
+/// The upper bounds of the buckets of the [`timed_channel`], from a microsecond to a second by tens.
+const DEFAULT_BOUNDS: [Duration; 7] = [
+    Duration::from_micros(1),
+    Duration::from_micros(10),
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+];
+
+/// Create a pair of the [`TimedSender`] and the [`TimedReceiver`] of a channel measuring
+/// how long each value dwells in it, bucketed by the default bounds, from a microsecond
+/// to a second by tens.
+pub fn timed_channel<T>() -> (TimedSender<T>, TimedReceiver<T>) {
+    timed_channel_with_buckets(DEFAULT_BOUNDS.to_vec())
+}
+
+/// Create a pair of the [`TimedSender`] and the [`TimedReceiver`] of a channel measuring
+/// how long each value dwells in it, bucketed by the upper `bounds` given.
+///
+/// The bounds are sorted, and an extra bucket is added for the dwell times above all of them.
+pub fn timed_channel_with_buckets<T>(mut bounds: Vec<Duration>) -> (TimedSender<T>, TimedReceiver<T>) {
+    bounds.sort();
+
+    let (tx, rx) = channel();
+
+    let clock = rx.clock.clone();
+
+    let counts = vec![0; bounds.len() + 1];
+
+    (TimedSender { tx, clock }, TimedReceiver { rx, histogram: RefCell::new(Histogram { bounds, counts }) })
+}
+
+/// The sending half of the [`timed_channel`], stamping each value with the time it's sent.
+pub struct TimedSender<T> {
+    tx: Sender<(Instant, T)>,
+    clock: Arc<dyn Clock>,
+}
+
+impl<T> TimedSender<T> {
+    /// Stamp the value and send it. It works just like [`Sender::send`].
+    pub fn send(&self, t: T) -> Result<(), mpsc::SendError<T>> {
+        self.tx.send((self.clock.now(), t)).map_err(|mpsc::SendError((_, t))| mpsc::SendError(t))
+    }
+}
+
+impl<T> Clone for TimedSender<T> {
+    fn clone(&self) -> Self {
+        Self { tx: self.tx.clone(), clock: self.clock.clone() }
+    }
+}
+
+/// The receiving half of the [`timed_channel`], which can be registered with the [`mio::poll::Poll`].
+///
+/// It works just like the [`Receiver`], while it keeps the histogram of the dwell times received.
+pub struct TimedReceiver<T> {
+    rx: Receiver<(Instant, T)>,
+    histogram: RefCell<Histogram>,
+}
+
+/// A histogram of the dwell times of the values received by the [`TimedReceiver`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    /// The upper bounds of the buckets, inclusive, in the ascending order.
+    pub bounds: Vec<Duration>,
+    /// The number of the values in each bucket, with an extra one for the dwell times above all the bounds.
+    pub counts: Vec<usize>,
+}
+
+impl<T> TimedReceiver<T> {
+    /// Try to receive a value along with how long it dwelt in the channel,
+    /// recording the dwell time in the histogram.
+    ///
+    /// It returns nothing if the channel is empty or disconnected.
+    pub fn try_recv_timed(&self) -> Option<(T, Duration)> {
+        let (sent, t) = self.rx.try_recv().ok()?;
+
+        let dwell = self.rx.clock.now().saturating_duration_since(sent);
+
+        let mut histogram = self.histogram.borrow_mut();
+
+        let bucket = histogram.bounds.partition_point(|bound| *bound < dwell);
+
+        histogram.counts[bucket] += 1;
+
+        Some((t, dwell))
+    }
+
+    /// Return a snapshot of the histogram of the dwell times received so far.
+    pub fn histogram_snapshot(&self) -> Histogram {
+        self.histogram.borrow().clone()
+    }
+
+    /// Return the [`mio::Interest`] of the events the receiver causes, which is always readable.
+    pub fn supported_interest(&self) -> mio::Interest {
+        self.rx.supported_interest()
+    }
+}
+
+impl<T> event::Source for TimedReceiver<T> {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        self.rx.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        self.rx.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        self.rx.deregister(registry)
+    }
+}
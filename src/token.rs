@@ -0,0 +1,147 @@
+use std::{cell::Cell, collections::HashSet, fmt, io, marker::PhantomData, sync::{Arc, Mutex}};
+
+use mio::{event::Source, Events, Interest, Token};
+
+use crate::{waker::{already_exists, lock}, Receiver};
+
+/// A [`Token`] tied to the type of the values of the channel registered with it.
+///
+/// It's returned by [`Receiver::register_typed`] and accepted by [`Receiver::drain_for`],
+/// so that a token of one channel can't be mixed up with another one of a different type.
+pub struct ChannelToken<T> {
+    token: Token,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> ChannelToken<T> {
+    /// Return the underlying [`Token`].
+    pub fn token(&self) -> Token {
+        self.token
+    }
+}
+
+impl<T> Clone for ChannelToken<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ChannelToken<T> {}
+
+impl<T> PartialEq for ChannelToken<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.token == other.token
+    }
+}
+
+impl<T> Eq for ChannelToken<T> {}
+
+impl<T> fmt::Debug for ChannelToken<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ChannelToken").field(&self.token).finish()
+    }
+}
+
+impl<T> From<ChannelToken<T>> for Token {
+    fn from(token: ChannelToken<T>) -> Self {
+        token.token
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Register the receiver with the `registry` just like [`Source::register`],
+    /// and return the [`Token`] tied to the type of the values.
+    pub fn register_typed(&mut self, registry: &mio::Registry, token: Token) -> io::Result<ChannelToken<T>> {
+        self.register(registry, token, Interest::READABLE)?;
+
+        Ok(ChannelToken { token, _marker: PhantomData })
+    }
+
+    /// Drain the values if any of the `events` is of the `token`, or return nothing otherwise.
+    pub fn drain_for(&self, token: ChannelToken<T>, events: &Events) -> Vec<T> {
+        let mut values = Vec::new();
+
+        if events.iter().any(|event| event.token() == token.token) {
+            while let Ok(t) = self.try_recv() {
+                values.push(t);
+            }
+        }
+
+        values
+    }
+}
+
+/// A set of the [`Token`]s in use by the receivers on a registry, to catch the collisions.
+///
+/// It's opted into by [`crate::ChannelBuilder::token_set`], so that registering a receiver
+/// with a [`Token`] already used by another one fails with [`io::ErrorKind::AlreadyExists`],
+/// rather than making the wakes ambiguous. A [`Token`] is released once the receiver
+/// is deregistered or dropped. The clones share the same set.
+#[derive(Debug, Default, Clone)]
+pub struct SharedTokenSet {
+    tokens: Arc<Mutex<HashSet<Token>>>,
+}
+
+impl SharedTokenSet {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return `true` if the `token` is in use by any receiver.
+    pub fn contains(&self, token: Token) -> bool {
+        lock(&self.tokens).contains(&token)
+    }
+
+    fn claim(&self, token: Token, name: Option<&str>) -> io::Result<()> {
+        if !lock(&self.tokens).insert(token) {
+            return Err(already_exists("the token is already in use by another receiver", name));
+        }
+
+        Ok(())
+    }
+
+    fn release(&self, token: Token) {
+        lock(&self.tokens).remove(&token);
+    }
+}
+
+/// The [`Token`] claimed by a receiver in a [`SharedTokenSet`], which is released when it's dropped.
+pub(crate) struct TokenClaim {
+    set: SharedTokenSet,
+    token: Cell<Option<Token>>,
+}
+
+impl TokenClaim {
+    pub(crate) fn new(set: SharedTokenSet) -> Self {
+        Self { set, token: Cell::new(None) }
+    }
+
+    /// Claim the `token` in place of the one claimed before, if it's not the same,
+    /// naming the channel by `name` on the failure.
+    pub(crate) fn claim(&self, token: Token, name: Option<&str>) -> io::Result<()> {
+        if self.token.get() == Some(token) {
+            return Ok(());
+        }
+
+        self.set.claim(token, name)?;
+
+        self.release();
+
+        self.token.set(Some(token));
+
+        Ok(())
+    }
+
+    pub(crate) fn release(&self) {
+        if let Some(token) = self.token.take() {
+            self.set.release(token);
+        }
+    }
+}
+
+impl Drop for TokenClaim {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
@@ -0,0 +1,215 @@
+use std::{io, sync::{Arc, Mutex, MutexGuard, PoisonError, atomic::{AtomicBool, Ordering}}};
+
+use mio::Token;
+
+use crate::WakeOutcome;
+
+/// Lock the `mutex` even if it's poisoned.
+///
+/// The state shared by the senders and the receiver stays consistent on its own
+/// even if a thread panics while holding it, so that a panic on one side should never
+/// propagate to the other, e.g. into a [`Drop`] racing with a send.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Create an [`io::ErrorKind::AlreadyExists`] error with the `message`, followed by the `name` of the channel if any.
+pub(crate) fn already_exists(message: &str, name: Option<&str>) -> io::Error {
+    match name {
+        Some(name) => io::Error::new(io::ErrorKind::AlreadyExists, format!("{}: {}", message, name)),
+        None => io::Error::new(io::ErrorKind::AlreadyExists, message),
+    }
+}
+
+/// An opaque identity of a [`mio::Registry`].
+///
+/// Mio doesn't expose the identity of a registry, so it's the file descriptor of the selector
+/// on Unix, or the address of the registry elsewhere. Hence a clone by [`mio::Registry::try_clone`]
+/// has an identity of its own, and so may a registry moved on the platforms other than Unix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RegistryId(usize);
+
+impl RegistryId {
+    pub(crate) fn of(registry: &mio::Registry) -> Self {
+        #[cfg(unix)]
+        let id = std::os::unix::io::AsRawFd::as_raw_fd(registry) as usize;
+
+        #[cfg(not(unix))]
+        let id = registry as *const mio::Registry as usize;
+
+        Self(id)
+    }
+}
+
+/// The waker of a channel, which wakes up the [`mio::poll::Poll`] it's registered with by its [`Token`].
+///
+/// Mio allows only a single [`mio::Waker`] per poll, while every channel has a waker of its own,
+/// so that on Unix it's a pair of connected sockets whose reading end is registered with the poll,
+/// each wake writing a byte to the other end. Hence any number of them, as well as a [`mio::Waker`]
+/// owned by the user, can be registered with the same poll.
+///
+/// Elsewhere it falls back to a [`mio::Waker`], so that no other [`mio::Waker`] should be created
+/// on the same poll, nor more than one channel registered with it.
+#[derive(Debug)]
+pub struct Waker {
+    #[cfg(unix)]
+    tx: std::os::unix::net::UnixStream,
+    #[cfg(unix)]
+    rx: mio::net::UnixStream,
+    #[cfg(not(unix))]
+    inner: mio::Waker,
+}
+
+impl Waker {
+    #[cfg(unix)]
+    fn new(registry: &mio::Registry, token: Token) -> io::Result<Self> {
+        let (tx, rx) = std::os::unix::net::UnixStream::pair()?;
+
+        tx.set_nonblocking(true)?;
+
+        rx.set_nonblocking(true)?;
+
+        let mut rx = mio::net::UnixStream::from_std(rx);
+
+        registry.register(&mut rx, token, mio::Interest::READABLE)?;
+
+        Ok(Self { tx, rx })
+    }
+
+    #[cfg(not(unix))]
+    fn new(registry: &mio::Registry, token: Token) -> io::Result<Self> {
+        Ok(Self { inner: mio::Waker::new(registry, token)? })
+    }
+
+    /// Wake up the [`mio::poll::Poll`]. It works just like [`mio::Waker::wake`].
+    pub fn wake(&self) -> io::Result<()> {
+        #[cfg(unix)]
+        loop {
+            match io::Write::write(&mut &self.tx, &[1]) {
+                Ok(_) => return Ok(()),
+                // The bytes are never read but here, so that the socket fills up
+                // after a number of wakes, and is emptied to write again.
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => self.drain()?,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        #[cfg(not(unix))]
+        self.inner.wake()
+    }
+
+    #[cfg(unix)]
+    fn drain(&self) -> io::Result<()> {
+        let mut buf = [0; 4096];
+
+        loop {
+            match io::Read::read(&mut &self.rx, &mut buf) {
+                Ok(0) => return Ok(()),
+                Ok(_) => continue,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// A [`Waker`] along with the [`Token`] and the registry it's registered with.
+#[derive(Debug, Clone)]
+pub(crate) struct Registration {
+    pub(crate) waker: Arc<Waker>,
+    pub(crate) token: Token,
+    pub(crate) registry: RegistryId,
+}
+
+impl Registration {
+    /// Create a [`Waker`] registered with the `registry` by the `token`.
+    pub(crate) fn new(registry: &mio::Registry, token: Token) -> io::Result<Self> {
+        let waker = Waker::new(registry, token)?;
+
+        Ok(Self { waker: Arc::new(waker), token, registry: RegistryId::of(registry) })
+    }
+}
+
+/// A slot for the [`Registration`] of a source, which is empty until it's registered.
+#[derive(Default)]
+pub(crate) struct WakerSlot(Mutex<Option<Registration>>);
+
+impl WakerSlot {
+    /// Fill the slot with a new [`Waker`] unless it's already filled.
+    ///
+    /// It fails if the slot is already filled with another `token`, naming the channel by `name` if any.
+    /// It returns `true` if the slot is newly filled.
+    pub(crate) fn register(&self, registry: &mio::Registry, token: Token, name: Option<&str>) -> io::Result<bool> {
+        let mut slot = lock(&self.0);
+
+        match &*slot {
+            None => {
+                *slot = Some(Registration::new(registry, token)?);
+
+                Ok(true)
+            }
+            Some(registration) if registration.token != token => {
+                Err(already_exists("the source is already registered with another token", name))
+            }
+            Some(_) => Ok(false),
+        }
+    }
+
+    /// Fill the slot with a new [`Waker`] even if it's already filled.
+    pub(crate) fn reregister(&self, registry: &mio::Registry, token: Token) -> io::Result<()> {
+        self.set(Registration::new(registry, token)?);
+
+        Ok(())
+    }
+
+    pub(crate) fn set(&self, registration: Registration) {
+        *lock(&self.0) = Some(registration);
+    }
+
+    pub(crate) fn clear(&self) {
+        *lock(&self.0) = None;
+    }
+
+    pub(crate) fn waker(&self) -> Option<Arc<Waker>> {
+        lock(&self.0).as_ref().map(|registration| registration.waker.clone())
+    }
+
+    pub(crate) fn is_registered_with(&self, registry: &mio::Registry, token: Token) -> bool {
+        lock(&self.0).as_ref().is_some_and(|registration| registration.token == token && registration.registry == RegistryId::of(registry))
+    }
+
+    pub(crate) fn token(&self) -> Option<Token> {
+        lock(&self.0).as_ref().map(|registration| registration.token)
+    }
+
+    pub(crate) fn is_registered(&self) -> bool {
+        lock(&self.0).is_some()
+    }
+
+    /// Wake up the [`mio::poll::Poll`] if the slot is filled.
+    pub(crate) fn wake(&self) -> WakeOutcome {
+        self.wake_or_mark(None)
+    }
+
+    /// Wake up the [`mio::poll::Poll`] if the slot is filled, or set the `missed` flag otherwise.
+    ///
+    /// The flag is set while the slot is locked, so that it's always observed
+    /// by the one filling the slot right after.
+    pub(crate) fn wake_or_mark(&self, missed: Option<&AtomicBool>) -> WakeOutcome {
+        match &*lock(&self.0) {
+            Some(registration) => match registration.waker.wake() {
+                Ok(()) => WakeOutcome::Woken,
+                Err(e) => WakeOutcome::Failed(e),
+            },
+            None => {
+                if let Some(missed) = missed {
+                    missed.store(true, Ordering::SeqCst);
+                }
+
+                WakeOutcome::NoWaker
+            }
+        }
+    }
+}
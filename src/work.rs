@@ -0,0 +1,162 @@
+use std::{io, sync::{mpsc, Arc, Mutex, atomic::{AtomicUsize, Ordering}}};
+
+use mio::{event, Token};
+
+use crate::waker::{lock, WakerSlot};
+
+/// Create a pair of the [`WorkSender`] and the [`WorkReceiver`] of a work-sharing channel.
+///
+/// Unlike [`crate::SharedReceiver`], whose clones share a single registration,
+/// each clone of the [`WorkReceiver`] can be registered with its own [`mio::poll::Poll`] and [`Token`].
+/// A value sent wakes up all of them, while only one of them receives it.
+pub fn work_channel<T>() -> (WorkSender<T>, WorkReceiver<T>) {
+    let (tx, rx) = mpsc::channel();
+
+    let waker = Arc::new(WakerSlot::default());
+
+    let shared = Arc::new(Work {
+        rx: Mutex::new(Some(rx)),
+        wakers: Mutex::new(vec![waker.clone()]),
+        senders: AtomicUsize::new(1),
+        receivers: AtomicUsize::new(1),
+    });
+
+    (WorkSender { tx, shared: WorkSenderRef(shared.clone()) }, WorkReceiver { shared, waker })
+}
+
+struct Work<T> {
+    // It's dropped along with the last receiver, so that the senders fail from then on.
+    rx: Mutex<Option<mpsc::Receiver<T>>>,
+    wakers: Mutex<Vec<Arc<WakerSlot>>>,
+    senders: AtomicUsize,
+    receivers: AtomicUsize,
+}
+
+impl<T> Work<T> {
+    fn wake_all(&self) {
+        for waker in lock(&self.wakers).iter() {
+            waker.wake();
+        }
+    }
+}
+
+/// The sending half of the [`work_channel`].
+pub struct WorkSender<T> {
+    tx: mpsc::Sender<T>,
+    // It's dropped after the `tx`, so that the channel is already disconnected
+    // when the last sender wakes up the receivers.
+    shared: WorkSenderRef<T>,
+}
+
+impl<T> WorkSender<T> {
+    /// Send a value. It works just like [`mpsc::Sender::send`].
+    /// After sending it, it's waking up the [`mio::poll::Poll`]s of all the receivers registered.
+    ///
+    /// It fails once all the receivers are dropped.
+    pub fn send(&self, t: T) -> Result<(), mpsc::SendError<T>> {
+        self.tx.send(t)?;
+
+        self.shared.0.wake_all();
+
+        Ok(())
+    }
+}
+
+impl<T> Clone for WorkSender<T> {
+    fn clone(&self) -> Self {
+        Self { tx: self.tx.clone(), shared: self.shared.clone() }
+    }
+}
+
+/// A handle to the [`Work`] state counting the senders.
+struct WorkSenderRef<T>(Arc<Work<T>>);
+
+impl<T> Clone for WorkSenderRef<T> {
+    fn clone(&self) -> Self {
+        self.0.senders.fetch_add(1, Ordering::SeqCst);
+
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Drop for WorkSenderRef<T> {
+    fn drop(&mut self) {
+        if self.0.senders.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.wake_all();
+        }
+    }
+}
+
+/// The receiving half of the [`work_channel`], which can be registered with the [`mio::poll::Poll`].
+///
+/// It ignores the [`mio::Interest`] and always cause readable events.
+/// Each clone has a registration of its own.
+pub struct WorkReceiver<T> {
+    shared: Arc<Work<T>>,
+    waker: Arc<WakerSlot>,
+}
+
+impl<T> WorkReceiver<T> {
+    /// Try to receive a value. It works just like [`mpsc::Receiver::try_recv`].
+    ///
+    /// Since all the clones are woken up by a value, the others may find the channel empty.
+    pub fn try_recv(&self) -> Result<T, mpsc::TryRecvError> {
+        match &*lock(&self.shared.rx) {
+            Some(rx) => rx.try_recv(),
+            None => Err(mpsc::TryRecvError::Disconnected),
+        }
+    }
+}
+
+impl<T> Clone for WorkReceiver<T> {
+    fn clone(&self) -> Self {
+        let waker = Arc::new(WakerSlot::default());
+
+        lock(&self.shared.wakers).push(waker.clone());
+
+        self.shared.receivers.fetch_add(1, Ordering::SeqCst);
+
+        Self { shared: self.shared.clone(), waker }
+    }
+}
+
+impl<T> Drop for WorkReceiver<T> {
+    fn drop(&mut self) {
+        lock(&self.shared.wakers).retain(|waker| !Arc::ptr_eq(waker, &self.waker));
+
+        if self.shared.receivers.fetch_sub(1, Ordering::SeqCst) == 1 {
+            // The values left are dropped outside the lock, since their drops may send again.
+            let rx = lock(&self.shared.rx).take();
+
+            drop(rx);
+        }
+    }
+}
+
+impl<T> event::Source for WorkReceiver<T> {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        _: mio::Interest,
+    ) -> io::Result<()> {
+        self.waker.register(registry, token, None)?;
+
+        Ok(())
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        _: mio::Interest,
+    ) -> io::Result<()> {
+        self.waker.reregister(registry, token)
+    }
+
+    fn deregister(&mut self, _: &mio::Registry) -> io::Result<()> {
+        self.waker.clear();
+
+        Ok(())
+    }
+}
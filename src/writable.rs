@@ -0,0 +1,77 @@
+use std::{io, ops::Deref};
+
+use mio::{event, Token};
+
+use crate::{sync_channel, Receiver, SyncSender};
+
+/// Create a pair of the [`WritableSender`] and the [`Receiver`] of a bounded channel.
+///
+/// Unlike [`sync_channel`], the sender implements the [`event::Source`] as well,
+/// so that a producer can be woken up whenever there's room to send.
+pub fn writable_channel<T>(bound: usize) -> (WritableSender<T>, Receiver<T>) {
+    let (tx, rx) = sync_channel(bound);
+
+    (WritableSender { tx }, rx)
+}
+
+/// A [`SyncSender`] which can be registered with the [`mio::poll::Poll`].
+///
+/// It ignores the [`mio::Interest`] and causes an event whenever the [`Receiver`] receives a value
+/// or is dropped. An event means that there may be room to send, so that it should be checked
+/// by [`SyncSender::try_send`]. The clones share a single registration.
+///
+/// Note that the event is readable, not writable, since it's caused by the [`crate::Waker`] of the channel,
+/// whose events are always readable. Tell it by the token rather than by [`mio::event::Event::is_writable`].
+pub struct WritableSender<T> {
+    tx: SyncSender<T>,
+}
+
+impl<T> WritableSender<T> {
+    /// Return the [`mio::Interest`] of the events the sender causes, which is always readable.
+    ///
+    /// Although the events mean that there may be room to send, they're delivered as readable,
+    /// since they're caused by the [`crate::Waker`] of the channel.
+    pub fn supported_interest(&self) -> mio::Interest {
+        mio::Interest::READABLE
+    }
+}
+
+impl<T> Deref for WritableSender<T> {
+    type Target = SyncSender<T>;
+
+    fn deref(&self) -> &SyncSender<T> {
+        &self.tx
+    }
+}
+
+impl<T> Clone for WritableSender<T> {
+    fn clone(&self) -> Self {
+        Self { tx: self.tx.clone() }
+    }
+}
+
+impl<T> event::Source for WritableSender<T> {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        _: mio::Interest,
+    ) -> io::Result<()> {
+        self.tx.register_space(registry, token)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: Token,
+        _: mio::Interest,
+    ) -> io::Result<()> {
+        self.tx.register_space(registry, token)
+    }
+
+    fn deregister(&mut self, _: &mio::Registry) -> io::Result<()> {
+        self.tx.deregister_space();
+
+        Ok(())
+    }
+}
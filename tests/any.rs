@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use mio_channel::AnyReceiver;
+
+    #[test]
+    fn test_any_receiver() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx0, rx0) = mio_channel::channel::<u32>();
+
+        let (tx1, rx1) = mio_channel::channel::<String>();
+
+        let mut rxs: Vec<AnyReceiver> = Vec::from([rx0.into(), rx1.into()]);
+
+        for (i, rx) in rxs.iter_mut().enumerate() {
+            poll.registry().register(rx, mio::Token(i), mio::Interest::READABLE)?;
+        }
+
+        tx1.send("Hello world!".to_string())?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        let event = events.iter().next().ok_or("no event")?;
+
+        assert_eq!(event.token(), mio::Token(1));
+
+        let rx = &rxs[event.token().0];
+
+        assert!(rx.downcast_recv::<u32>().is_none());
+
+        assert_eq!(rx.downcast_recv::<String>(), Some(Ok("Hello world!".to_string())));
+
+        drop(tx0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    const CHANNEL: mio::Token = mio::Token(0);
+
+    #[test]
+    fn test_recv_async() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let (tx, rx) = mio_channel::channel();
+
+        let (bridge, rx) = mio_channel::MioBridge::new(rx, poll.registry(), CHANNEL)?;
+
+        let done = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let poller = {
+            let done = done.clone();
+
+            std::thread::spawn(move || -> std::io::Result<()> {
+                let mut events = mio::Events::with_capacity(1);
+
+                while !done.load(std::sync::atomic::Ordering::SeqCst) {
+                    poll.poll(&mut events, Some(std::time::Duration::from_millis(10)))?;
+
+                    bridge.dispatch(&events);
+                }
+
+                Ok(())
+            })
+        };
+
+        let sender = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+
+            tx.send("Hello world!")
+        });
+
+        assert_eq!(futures::executor::block_on(rx.recv_async()), Some("Hello world!"));
+
+        sender.join().map_err(|_| "panicked")??;
+
+        assert_eq!(futures::executor::block_on(rx.recv_async()), None);
+
+        done.store(true, std::sync::atomic::Ordering::SeqCst);
+
+        poller.join().map_err(|_| "panicked")??;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,363 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::TryRecvError;
+
+    use mio_channel::{ChannelBuilder, TriggerMode, WakeOutcome};
+
+    const CHANNEL: mio::Token = mio::Token(0);
+
+    #[test]
+    fn test_auto_deregister_on_disconnect() -> Result<(), Box<dyn std::error::Error>> {
+        let poll = mio::Poll::new()?;
+
+        let (tx, mut rx) = ChannelBuilder::new().auto_deregister_on_disconnect(true).build();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        tx.send("Hello world!")?;
+
+        drop(tx);
+
+        assert_eq!(rx.try_recv()?, "Hello world!");
+
+        assert!(rx.is_registered());
+
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+
+        assert!(!rx.is_registered());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_default() -> Result<(), Box<dyn std::error::Error>> {
+        let poll = mio::Poll::new()?;
+
+        let (tx, mut rx) = ChannelBuilder::new().build();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        assert!(matches!(tx.send_with_outcome(0)?, WakeOutcome::Woken));
+
+        assert!(matches!(tx.send_with_outcome(1)?, WakeOutcome::Woken));
+
+        assert_eq!(rx.len(), 2);
+
+        drop(tx);
+
+        while rx.try_recv().is_ok() {}
+
+        assert!(rx.is_registered());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_coalesce() -> Result<(), Box<dyn std::error::Error>> {
+        let poll = mio::Poll::new()?;
+
+        let (tx, mut rx) = ChannelBuilder::new().coalesce(true).build();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        assert!(matches!(tx.send_with_outcome(0)?, WakeOutcome::Woken));
+
+        assert!(matches!(tx.send_with_outcome(1)?, WakeOutcome::Coalesced));
+
+        assert_eq!(rx.len(), 2);
+
+        assert_eq!(rx.try_recv()?, 0);
+
+        assert!(matches!(tx.send_with_outcome(2)?, WakeOutcome::Coalesced));
+
+        assert_eq!(rx.try_recv()?, 1);
+
+        assert_eq!(rx.try_recv()?, 2);
+
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+
+        assert!(matches!(tx.send_with_outcome(3)?, WakeOutcome::Woken));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_coalesce_with_auto_deregister() -> Result<(), Box<dyn std::error::Error>> {
+        let poll = mio::Poll::new()?;
+
+        let (tx, mut rx) = ChannelBuilder::new()
+            .coalesce(true)
+            .auto_deregister_on_disconnect(true)
+            .on_wake_error(|_| {})
+            .build_sync(1);
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        tx.send(0)?;
+
+        drop(tx);
+
+        assert_eq!(rx.try_recv()?, 0);
+
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+
+        assert!(!rx.is_registered());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_acknowledge() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = ChannelBuilder::new().coalesce(true).build();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        for i in 0..3 {
+            tx.send(i)?;
+        }
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        assert!(!events.is_empty());
+
+        assert_eq!(rx.try_recv()?, 0);
+
+        assert!(rx.is_wake_pending());
+
+        tx.send(3)?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert!(events.is_empty());
+
+        rx.acknowledge();
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        assert!(!events.is_empty());
+
+        for i in 1..4 {
+            assert_eq!(rx.try_recv()?, i);
+        }
+
+        Ok(())
+    }
+
+    fn count_events_after_partial_drain(mode: TriggerMode) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = ChannelBuilder::new().trigger_mode(mode).build();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        for i in 0..3 {
+            tx.send(i)?;
+        }
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().count(), 1);
+
+        assert_eq!(rx.steal(), vec![0, 1]);
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        Ok(events.iter().count())
+    }
+
+    #[test]
+    fn test_edge_trigger_mode() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(count_events_after_partial_drain(TriggerMode::Edge)?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_level_trigger_mode() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(count_events_after_partial_drain(TriggerMode::Level)?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_level_trigger_mode_after_full_drain() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = ChannelBuilder::new().trigger_mode(TriggerMode::Level).build();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        for i in 0..3 {
+            tx.send(i)?;
+        }
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().count(), 1);
+
+        assert_eq!(rx.try_recv()?, 0);
+
+        rx.resume_later();
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().count(), 1);
+
+        assert_eq!(rx.recv_many_timeout(8, std::time::Duration::ZERO), (vec![1, 2], false));
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_linger() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = ChannelBuilder::new().linger(std::time::Duration::from_millis(50), 100).build();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        for i in 0..10 {
+            assert!(matches!(tx.send_with_outcome(i)?, WakeOutcome::Coalesced));
+        }
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        assert_eq!(events.iter().count(), 1);
+
+        assert_eq!(rx.recv_many_timeout(10, std::time::Duration::ZERO).0, (0..10).collect::<Vec<_>>());
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_metrics_registry() -> Result<(), Box<dyn std::error::Error>> {
+        let poll = mio::Poll::new()?;
+
+        let registry = mio_channel::MetricsRegistry::new();
+
+        let (tx0, mut rx0) = ChannelBuilder::new().name("first").metrics_registry(&registry).build();
+
+        let (tx1, _rx1) = ChannelBuilder::new().name("second").metrics_registry(&registry).build();
+
+        poll.registry().register(&mut rx0, CHANNEL, mio::Interest::READABLE)?;
+
+        tx0.send(0)?;
+
+        tx0.send(1)?;
+
+        tx1.send(0)?;
+
+        let snapshot = registry.snapshot();
+
+        assert_eq!(snapshot.len(), 2);
+
+        assert_eq!(snapshot[0].0, "first");
+
+        assert_eq!((snapshot[0].1.sent, snapshot[0].1.woken), (2, 2));
+
+        assert_eq!(snapshot[1].0, "second");
+
+        assert_eq!((snapshot[1].1.sent, snapshot[1].1.woken), (1, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_set() -> Result<(), Box<dyn std::error::Error>> {
+        let poll = mio::Poll::new()?;
+
+        let set = mio_channel::SharedTokenSet::new();
+
+        let builder = ChannelBuilder::new().token_set(&set);
+
+        let (_tx0, mut rx0) = builder.clone().build::<()>();
+
+        let (_tx1, mut rx1) = builder.build::<()>();
+
+        // Each waker needs its own registry, since only a single one is allowed per registry in debug builds.
+        poll.registry().try_clone()?.register(&mut rx0, CHANNEL, mio::Interest::READABLE)?;
+
+        let e = poll.registry().try_clone()?.register(&mut rx1, CHANNEL, mio::Interest::READABLE).unwrap_err();
+
+        assert_eq!(e.kind(), std::io::ErrorKind::AlreadyExists);
+
+        assert!(!rx1.is_registered());
+
+        poll.registry().deregister(&mut rx0)?;
+
+        assert!(!set.contains(CHANNEL));
+
+        poll.registry().try_clone()?.register(&mut rx1, CHANNEL, mio::Interest::READABLE)?;
+
+        assert!(set.contains(CHANNEL));
+
+        drop(rx1);
+
+        assert!(!set.contains(CHANNEL));
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "blocking receive on a receiver registered with a poll")]
+    fn test_deny_blocking_recv() {
+        let poll = mio::Poll::new().unwrap();
+
+        let (tx, mut rx) = ChannelBuilder::new().deny_blocking_recv(true).build::<()>();
+
+        tx.send(()).unwrap();
+
+        assert_eq!(rx.recv(), Ok(()));
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE).unwrap();
+
+        let _ = rx.recv_timeout(std::time::Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_metrics_registry_counts_batches_and_forgets_dropped() -> Result<(), Box<dyn std::error::Error>> {
+        let registry = mio_channel::MetricsRegistry::new();
+
+        let builder = ChannelBuilder::new().metrics_registry(&registry);
+
+        let (tx, rx) = builder.clone().name("batch").build_sync(4);
+
+        tx.try_send_all(vec![0, 1, 2]).map_err(|_| "full")?;
+
+        let snapshot = registry.snapshot();
+
+        assert_eq!(snapshot.len(), 1);
+
+        assert_eq!(snapshot[0].1.sent, 3);
+
+        drop((tx, rx));
+
+        let (_tx, _rx) = builder.name("other").build::<()>();
+
+        let snapshot = registry.snapshot();
+
+        assert_eq!(snapshot.len(), 1);
+
+        assert_eq!(snapshot[0].0, "other");
+
+        Ok(())
+    }
+}
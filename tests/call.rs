@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    const CHANNEL: mio::Token = mio::Token(0);
+
+    #[test]
+    fn test_call() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, rx) = mio_channel::channel::<mio_channel::Call<i32, i32>>();
+
+        let handler = std::thread::spawn(move || {
+            let call = rx.recv()?;
+
+            let n = *call.request();
+
+            call.reply(n * 2).map_err(|_| "disconnected")?;
+
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+        });
+
+        let mut reply = tx.call(21)?;
+
+        poll.registry().register(&mut reply, CHANNEL, mio::Interest::READABLE)?;
+
+        let reply = loop {
+            if let Ok(reply) = reply.try_recv() {
+                break reply;
+            }
+
+            poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+        };
+
+        assert_eq!(reply, 42);
+
+        handler.join().map_err(|_| "panicked")?.map_err(|e| e.to_string())?;
+
+        drop(tx);
+
+        Ok(())
+    }
+}
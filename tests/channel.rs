@@ -26,4 +26,1535 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_channel_wakes_on_last_sender_drop() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::channel::<()>();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let cloned = tx.clone();
+
+        drop(tx);
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert!(events.is_empty());
+
+        drop(cloned);
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        assert!(!events.is_empty());
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Disconnected));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_channel_wakes_on_last_sender_drop() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::sync_channel::<()>(1);
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let handler = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+
+            drop(tx);
+        });
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        assert!(!events.is_empty());
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Disconnected));
+
+        let _ = handler.join();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_guard() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        tx.send(vec![1, 2, 3])?;
+
+        {
+            let guard = rx.recv_guard().ok_or("empty")?;
+
+            assert_eq!(guard.len(), 3);
+
+            assert_eq!(guard[0], 1);
+        }
+
+        assert!(rx.recv_guard().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_channel_eager() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::channel_eager(poll.registry(), CHANNEL)?;
+
+        tx.send("Hello world!")?;
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        assert!(poll.registry().register(&mut rx, mio::Token(1), mio::Interest::READABLE).is_err());
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        assert!(!events.is_empty());
+
+        assert_eq!(rx.try_recv()?, "Hello world!");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_send_if_below() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        assert!(tx.send_if_below(0, 2)?);
+
+        assert!(tx.send_if_below(1, 2)?);
+
+        assert!(!tx.send_if_below(2, 2)?);
+
+        assert_eq!(rx.len(), 2);
+
+        assert_eq!(rx.try_recv()?, 0);
+
+        assert!(tx.send_if_below(3, 2)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_channel_with_heartbeat() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (_tx, mut rx) = mio_channel::channel_with_heartbeat::<()>(std::time::Duration::from_millis(50));
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let now = std::time::Instant::now();
+
+        for _ in 0..3 {
+            poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+            assert!(!events.is_empty());
+
+            assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Empty));
+        }
+
+        let elapsed = now.elapsed();
+
+        assert!(elapsed >= std::time::Duration::from_millis(100));
+
+        assert!(elapsed < std::time::Duration::from_millis(1000));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_filtered() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        for i in [1, 3, 4, 5, 6] {
+            tx.send(i)?;
+        }
+
+        assert_eq!(rx.recv_filtered(|i| i % 2 == 0), (Some(4), 2));
+
+        assert_eq!(rx.recv_filtered(|i| i % 2 == 0), (Some(6), 1));
+
+        assert_eq!(rx.recv_filtered(|i| i % 2 == 0), (None, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pause_and_resume() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        rx.pause();
+
+        for i in 0..3 {
+            tx.send(i)?;
+        }
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert!(events.is_empty());
+
+        rx.resume();
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        assert_eq!(events.iter().count(), 1);
+
+        for i in 0..3 {
+            assert_eq!(rx.try_recv()?, i);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_send_with_outcome() -> Result<(), Box<dyn std::error::Error>> {
+        let poll = mio::Poll::new()?;
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        assert!(matches!(tx.send_with_outcome(0)?, mio_channel::WakeOutcome::NoWaker));
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        assert!(matches!(tx.send_with_outcome(1)?, mio_channel::WakeOutcome::Woken));
+
+        rx.pause();
+
+        assert!(matches!(tx.send_with_outcome(2)?, mio_channel::WakeOutcome::Coalesced));
+
+        // `WakeOutcome::Failed` isn't exercised here, since there's no way to make the waker fail
+        // from the outside: the waker owns its own descriptor, which outlives the poll, and mio
+        // drains it and retries by itself when it's full.
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_register_scoped() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        let registry = poll.registry().try_clone()?;
+
+        {
+            let guard = rx.register_scoped(&registry, CHANNEL)?;
+
+            assert!(guard.is_registered());
+
+            tx.send("Hello world!")?;
+
+            poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+            assert_eq!(guard.try_recv()?, "Hello world!");
+        }
+
+        assert!(!rx.is_registered());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_send_bounded() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        let handler = std::thread::spawn(move || {
+            for i in 0..4 {
+                let _ = tx.send_bounded(i, 2);
+            }
+        });
+
+        std::thread::sleep(std::time::Duration::from_millis(100));
+
+        assert_eq!(rx.len(), 2);
+
+        assert_eq!(rx.try_recv()?, 0);
+
+        std::thread::sleep(std::time::Duration::from_millis(100));
+
+        assert_eq!(rx.len(), 2);
+
+        assert_eq!(rx.try_recv()?, 1);
+
+        assert_eq!(rx.recv_timeout(std::time::Duration::from_millis(1000))?, 2);
+
+        assert_eq!(rx.recv_timeout(std::time::Duration::from_millis(1000))?, 3);
+
+        let _ = handler.join();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_on_message() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        for i in 0..3 {
+            tx.send(i)?;
+        }
+
+        let mut count = 0;
+
+        rx.on_message(|_| count += 1)?;
+
+        assert_eq!(count, 3);
+
+        drop(tx);
+
+        assert_eq!(rx.on_message(|_| count += 1), Err(std::sync::mpsc::TryRecvError::Disconnected));
+
+        assert_eq!(count, 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_last_wake_delivered() -> Result<(), Box<dyn std::error::Error>> {
+        let poll = mio::Poll::new()?;
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        tx.send(0)?;
+
+        assert!(!tx.last_wake_delivered());
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        tx.send(1)?;
+
+        assert!(tx.last_wake_delivered());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_send() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        tx.try_send(0)?;
+
+        assert_eq!(rx.try_recv()?, 0);
+
+        drop(rx);
+
+        assert_eq!(tx.try_send(1), Err(std::sync::mpsc::TrySendError::Disconnected(1)));
+
+        let (tx, rx) = mio_channel::sync_channel(1);
+
+        tx.try_send(0)?;
+
+        assert_eq!(tx.try_send(1), Err(std::sync::mpsc::TrySendError::Full(1)));
+
+        assert_eq!(rx.try_recv()?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_many_timeout() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        for i in 0..3 {
+            tx.send(i)?;
+        }
+
+        let timeout = std::time::Duration::from_millis(100);
+
+        assert_eq!(rx.recv_many_timeout(2, timeout), (vec![0, 1], false));
+
+        assert_eq!(rx.recv_many_timeout(2, timeout), (vec![2], false));
+
+        tx.send(3)?;
+
+        drop(tx);
+
+        assert_eq!(rx.recv_many_timeout(2, timeout), (vec![3], true));
+
+        assert_eq!(rx.recv_many_timeout(2, timeout), (vec![], true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sized_channel() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::sized_channel(|s: &String| s.len());
+
+        let cloned = tx.clone();
+
+        tx.send("Hello".to_string())?;
+
+        cloned.send("world!".to_string())?;
+
+        assert_eq!(rx.pending_bytes(), 11);
+
+        assert_eq!(rx.try_recv()?, "Hello");
+
+        assert_eq!(rx.pending_bytes(), 6);
+
+        assert_eq!(rx.try_recv()?, "world!");
+
+        assert_eq!(rx.pending_bytes(), 0);
+
+        Ok(())
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum AppError {
+        Idle,
+        Shutdown,
+    }
+
+    fn handle(rx: &mio_channel::Receiver<i32>) -> Result<i32, AppError> {
+        let t = rx.try_recv_map_err(|e| match e {
+            std::sync::mpsc::TryRecvError::Empty => AppError::Idle,
+            std::sync::mpsc::TryRecvError::Disconnected => AppError::Shutdown,
+        })?;
+
+        Ok(t * 2)
+    }
+
+    #[test]
+    fn test_try_recv_map_err() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        assert_eq!(handle(&rx), Err(AppError::Idle));
+
+        tx.send(1)?;
+
+        drop(tx);
+
+        assert_eq!(handle(&rx), Ok(2));
+
+        assert_eq!(handle(&rx), Err(AppError::Shutdown));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_register_all_rolls_back() -> Result<(), Box<dyn std::error::Error>> {
+        let poll = mio::Poll::new()?;
+
+        let (_tx0, mut rx0) = mio_channel::channel::<()>();
+
+        let (_tx1, mut rx1) = mio_channel::channel::<()>();
+
+        let (_tx2, mut rx2) = mio_channel::channel::<()>();
+
+        poll.registry().register(&mut rx1, mio::Token(9), mio::Interest::READABLE)?;
+
+        let mut receivers = [(&mut rx0, mio::Token(0)), (&mut rx1, mio::Token(1)), (&mut rx2, mio::Token(2))];
+
+        let result = mio_channel::register_all(poll.registry(), &mut receivers);
+
+        assert_eq!(result.map_err(|e| e.kind()), Err(std::io::ErrorKind::AlreadyExists));
+
+        assert!(!rx0.is_registered());
+
+        assert!(rx1.is_registered());
+
+        assert!(!rx2.is_registered());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_sender() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (old, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        old.send(0)?;
+
+        let tx = rx.replace_sender();
+
+        assert!(rx.is_empty());
+
+        assert_eq!(old.send(1), Err(std::sync::mpsc::SendError(1)));
+
+        tx.send(2)?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        assert!(!events.is_empty());
+
+        assert_eq!(rx.try_recv()?, 2);
+
+        drop(old);
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Empty));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_sync_sender() -> Result<(), Box<dyn std::error::Error>> {
+        let (old, mut rx) = mio_channel::sync_channel(1);
+
+        old.send(0)?;
+
+        let tx = rx.replace_sync_sender();
+
+        assert!(rx.is_empty());
+
+        assert!(old.try_send(1).is_err());
+
+        tx.try_send(2)?;
+
+        assert_eq!(tx.try_send(3), Err(std::sync::mpsc::TrySendError::Full(3)));
+
+        assert_eq!(rx.try_recv()?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "replacing the sender of a bounded channel with an unbounded one")]
+    fn test_replace_sender_of_sync_channel() {
+        let (_tx, mut rx) = mio_channel::sync_channel::<()>(1);
+
+        let _ = rx.replace_sender();
+    }
+
+    fn recv_plain(rx: &std::sync::mpsc::Receiver<&'static str>) -> Option<&'static str> {
+        rx.try_recv().ok()
+    }
+
+    #[test]
+    fn test_as_ref() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        tx.send("Hello world!")?;
+
+        assert_eq!(recv_plain(rx.as_ref()), Some("Hello world!"));
+
+        assert_eq!(recv_plain(rx.as_ref()), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_channel_with_idle_timeout() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (_tx, mut rx) = mio_channel::channel_with_idle_timeout::<()>(std::time::Duration::from_millis(50));
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let now = std::time::Instant::now();
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        assert!(!events.is_empty());
+
+        assert!(now.elapsed() < std::time::Duration::from_millis(1000));
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Empty));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_channel_with_idle_timeout_reset_by_sends() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::channel_with_idle_timeout(std::time::Duration::from_millis(100));
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let handler = std::thread::spawn(move || {
+            for i in 0..20 {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+
+                let _ = tx.send(i);
+            }
+
+            tx
+        });
+
+        let mut received = 0;
+
+        while received < 20 {
+            poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+            assert!(!events.is_empty());
+
+            let mut drained = 0;
+
+            while rx.try_recv().is_ok() {
+                drained += 1;
+            }
+
+            assert!(drained > 0);
+
+            received += drained;
+        }
+
+        let _tx = handler.join().map_err(|_| "panicked")?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        assert!(!events.is_empty());
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Empty));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_send_wakes_before_blocking() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = mio_channel::sync_channel(1);
+
+        // The wake of the first value is missed, since the receiver is not registered yet.
+        tx.send(0)?;
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let handler = std::thread::spawn(move || tx.send(1));
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        assert_eq!(events.iter().next().map(|event| event.token()), Some(CHANNEL));
+
+        assert_eq!(rx.try_recv()?, 0);
+
+        handler.join().map_err(|_| "panicked")??;
+
+        assert_eq!(rx.try_recv()?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_supported_interest() -> Result<(), Box<dyn std::error::Error>> {
+        let (_tx, rx) = mio_channel::channel::<()>();
+
+        assert!(rx.supported_interest().is_readable());
+
+        assert!(!rx.supported_interest().is_writable());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_coalesced() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        for c in ["A", "A", "A", "B"] {
+            tx.send(c)?;
+        }
+
+        assert_eq!(rx.recv_coalesced(), Some("A"));
+
+        assert_eq!(rx.len(), 1);
+
+        assert_eq!(rx.recv_coalesced(), Some("B"));
+
+        assert_eq!(rx.recv_coalesced(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_waker() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (_tx, mut rx) = mio_channel::channel::<()>();
+
+        assert!(rx.waker().is_none());
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        rx.waker().ok_or("no waker")?.wake()?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().next().map(|event| event.token()), Some(CHANNEL));
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Empty));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_capacity_hint() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, mut rx) = mio_channel::channel();
+
+        rx.set_capacity_hint(8);
+
+        for i in 0..4 {
+            tx.send(i)?;
+        }
+
+        let (batch, disconnected) = rx.recv_many_timeout(16, std::time::Duration::from_millis(10));
+
+        assert_eq!(batch, vec![0, 1, 2, 3]);
+
+        assert!(!disconnected);
+
+        assert!(batch.capacity() >= 8);
+
+        tx.send(4)?;
+
+        let batch = rx.steal();
+
+        assert_eq!(batch, vec![4]);
+
+        assert!(batch.capacity() >= 8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_channel_deferred() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = mio_channel::channel_deferred();
+
+        tx.send(0)?;
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().next().map(|event| event.token()), Some(CHANNEL));
+
+        assert_eq!(rx.try_recv()?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_drain_and_close() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        let handler = std::thread::spawn(move || {
+            let mut sent = 0;
+
+            while tx.send(sent).is_ok() {
+                sent += 1;
+            }
+
+            sent
+        });
+
+        let first = rx.recv_timeout(std::time::Duration::from_millis(1000))?;
+
+        let rest = rx.drain_and_close();
+
+        let sent = handler.join().map_err(|_| "panicked")?;
+
+        assert_eq!(first, 0);
+
+        assert_eq!(rest, (1..sent).collect::<Vec<_>>());
+
+        assert!(rx.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_drop_racing_with_send() -> Result<(), Box<dyn std::error::Error>> {
+        for _ in 0..100 {
+            let poll = mio::Poll::new()?;
+
+            let (tx, mut rx) = mio_channel::channel();
+
+            poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+            let handlers = (0..4)
+                .map(|_| {
+                    let tx = tx.clone();
+
+                    std::thread::spawn(move || while tx.send(()).is_ok() {})
+                })
+                .collect::<Vec<_>>();
+
+            drop(tx);
+
+            let _ = rx.recv_timeout(std::time::Duration::from_millis(1));
+
+            drop(rx);
+
+            drop(poll);
+
+            for handler in handlers {
+                handler.join().map_err(|_| "panicked")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_send_all() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::sync_channel(3);
+
+        assert_eq!(tx.try_send_all(vec![0, 1, 2, 3]), Err(vec![0, 1, 2, 3]));
+
+        assert!(rx.is_empty());
+
+        assert_eq!(tx.try_send_all(vec![0, 1, 2]), Ok(()));
+
+        assert_eq!(rx.len(), 3);
+
+        assert_eq!(tx.try_send_all(vec![3]), Err(vec![3]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_registered_with() -> Result<(), Box<dyn std::error::Error>> {
+        let poll = mio::Poll::new()?;
+
+        let (_tx, mut rx) = mio_channel::channel::<()>();
+
+        assert!(!rx.is_registered_with(poll.registry(), CHANNEL));
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        assert!(rx.is_registered_with(poll.registry(), CHANNEL));
+
+        assert!(!rx.is_registered_with(poll.registry(), mio::Token(1)));
+
+        let other = mio::Poll::new()?;
+
+        assert!(!rx.is_registered_with(other.registry(), CHANNEL));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_or_default() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        assert_eq!(rx.recv_or_default(), 0);
+
+        tx.send(1)?;
+
+        assert_eq!(rx.recv_or_default(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_chain_waker() -> Result<(), Box<dyn std::error::Error>> {
+        const MASTER: mio::Token = mio::Token(1);
+
+        let mut poll = mio::Poll::new()?;
+
+        let mut master = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        rx.chain_waker(std::sync::Arc::new(mio::Waker::new(master.registry(), MASTER)?));
+
+        tx.send(0)?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().next().map(|event| event.token()), Some(CHANNEL));
+
+        master.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().next().map(|event| event.token()), Some(MASTER));
+
+        rx.unchain_waker();
+
+        tx.send(1)?;
+
+        master.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert!(events.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fold_messages() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        for i in 1..=4 {
+            tx.send(i)?;
+        }
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        let sum = events.iter().filter(|event| event.token() == CHANNEL).fold(0, |sum, _| rx.fold_messages(sum, |sum, t| sum + t));
+
+        assert_eq!(sum, 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_actively_draining() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        rx.set_actively_draining(true);
+
+        assert!(matches!(tx.send_with_outcome(0)?, mio_channel::WakeOutcome::Coalesced));
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert!(events.is_empty());
+
+        assert_eq!(rx.try_recv()?, 0);
+
+        tx.send(1)?;
+
+        rx.set_actively_draining(false);
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().next().map(|event| event.token()), Some(CHANNEL));
+
+        assert!(matches!(tx.send_with_outcome(2)?, mio_channel::WakeOutcome::Woken));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        tx.send(0)?;
+
+        let _ = rx.drain_and_close();
+
+        let tx = rx.reset();
+
+        assert!(rx.is_empty());
+
+        assert!(rx.is_registered());
+
+        poll.poll(&mut events, Some(std::time::Duration::ZERO))?;
+
+        tx.send(1)?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().next().map(|event| event.token()), Some(CHANNEL));
+
+        assert_eq!(rx.try_recv()?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_send_deadline() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::sync_channel(1);
+
+        let handler = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+
+            let values = rx.recv_many_timeout(2, std::time::Duration::from_millis(1000)).0;
+
+            (rx, values)
+        });
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(1000);
+
+        tx.send_deadline(0, deadline)?;
+
+        tx.send_deadline(1, deadline)?;
+
+        let (_rx, values) = handler.join().map_err(|_| "panicked")?;
+
+        assert_eq!(values, vec![0, 1]);
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(50);
+
+        tx.send_deadline(2, deadline)?;
+
+        assert_eq!(tx.send_deadline(3, deadline), Err(mio_channel::SendTimeoutError::Timeout(3)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_handler() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        let log = std::cell::RefCell::new(Vec::new());
+
+        tx.send("a")?;
+
+        assert!(!rx.split_handler(|t| log.borrow_mut().push(t), || log.borrow_mut().push("disconnected")));
+
+        tx.send("b")?;
+
+        tx.send("c")?;
+
+        drop(tx);
+
+        assert!(rx.split_handler(|t| log.borrow_mut().push(t), || log.borrow_mut().push("disconnected")));
+
+        assert_eq!(*log.borrow(), vec!["a", "b", "c", "disconnected"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_peek_all() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, mut rx) = mio_channel::channel();
+
+        for i in 0..3 {
+            tx.send(i)?;
+        }
+
+        assert_eq!(rx.peek_all(), &[0, 1, 2]);
+
+        assert_eq!(rx.len(), 3);
+
+        assert_eq!(rx.commit(2), vec![0, 1]);
+
+        assert_eq!(rx.len(), 1);
+
+        tx.send(3)?;
+
+        assert_eq!(rx.peek_all(), &[2, 3]);
+
+        assert_eq!(rx.try_recv()?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_on_all_senders_dropped() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx0, rx) = mio_channel::channel::<()>();
+
+        let tx1 = tx0.clone();
+
+        let tx2 = tx1.clone();
+
+        let count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let called = count.clone();
+
+        rx.on_all_senders_dropped(move || {
+            called.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        });
+
+        drop(tx0);
+
+        drop(tx1);
+
+        assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        drop(tx2);
+
+        assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrival_rate() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        assert_eq!(rx.arrival_rate(), 0.0);
+
+        let handler = std::thread::spawn(move || {
+            for i in 0..10 {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+
+                tx.send(i)?;
+            }
+
+            Ok::<_, std::sync::mpsc::SendError<i32>>(())
+        });
+
+        for _ in 0..10 {
+            rx.recv_timeout(std::time::Duration::from_millis(1000))?;
+        }
+
+        handler.join().map_err(|_| "panicked")??;
+
+        // It's expected to be 50 per second, with a tolerance for the scheduling.
+        let rate = rx.arrival_rate();
+
+        assert!((20.0..=80.0).contains(&rate), "{}", rate);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_into_parts() -> Result<(), Box<dyn std::error::Error>> {
+        struct Custom {
+            rx: std::sync::mpsc::Receiver<i32>,
+            waker: mio_channel::ReceiverWaker,
+        }
+
+        impl mio::event::Source for Custom {
+            fn register(&mut self, registry: &mio::Registry, token: mio::Token, interests: mio::Interest) -> std::io::Result<()> {
+                self.waker.register(registry, token, interests)
+            }
+
+            fn reregister(&mut self, registry: &mio::Registry, token: mio::Token, interests: mio::Interest) -> std::io::Result<()> {
+                self.waker.reregister(registry, token, interests)
+            }
+
+            fn deregister(&mut self, registry: &mio::Registry) -> std::io::Result<()> {
+                self.waker.deregister(registry)
+            }
+        }
+
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, rx) = mio_channel::channel();
+
+        let (rx, waker) = rx.into_parts();
+
+        let mut custom = Custom { rx, waker };
+
+        poll.registry().register(&mut custom, CHANNEL, mio::Interest::READABLE)?;
+
+        tx.send(1)?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().map(|e| e.token()).collect::<Vec<_>>(), vec![CHANNEL]);
+
+        assert_eq!(custom.rx.try_recv()?, 1);
+
+        drop(tx);
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().map(|e| e.token()).collect::<Vec<_>>(), vec![CHANNEL]);
+
+        assert!(custom.rx.try_recv().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_into() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        for i in 0..3 {
+            tx.send(i)?;
+        }
+
+        let mut buf = [None; 4];
+
+        assert_eq!(rx.recv_into(&mut buf), 3);
+
+        assert_eq!(buf, [Some(0), Some(1), Some(2), None]);
+
+        assert!(rx.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_sender_is_full() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::sync_channel(2);
+
+        assert!(!tx.is_full());
+
+        tx.send(1)?;
+
+        assert!(!tx.is_full());
+
+        tx.send(2)?;
+
+        assert!(tx.is_full());
+
+        assert_eq!(rx.try_recv()?, 1);
+
+        assert!(!tx.is_full());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_drain_sorted() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        for i in [3, 1, 2] {
+            tx.send(i)?;
+        }
+
+        assert_eq!(rx.drain_sorted(), vec![1, 2, 3]);
+
+        assert!(rx.drain_sorted().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wake_batch() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let registry = mio_channel::MetricsRegistry::new();
+
+        let (tx, mut rx) = mio_channel::ChannelBuilder::new().name("batch").metrics_registry(&registry).build();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let mut guard = tx.wake_batch();
+
+        for i in 0..3 {
+            guard.send(i)?;
+        }
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().count(), 0);
+
+        drop(guard);
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().count(), 1);
+
+        let snapshot = registry.snapshot();
+
+        assert_eq!((snapshot[0].1.sent, snapshot[0].1.woken), (3, 1));
+
+        assert_eq!(rx.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_channel_named() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel_named::<()>("requests");
+
+        assert!(format!("{:?}", tx).contains("requests"));
+
+        assert!(format!("{:?}", rx).contains("requests"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_channel_named_on_conflict() -> Result<(), Box<dyn std::error::Error>> {
+        let poll = mio::Poll::new()?;
+
+        let set = mio_channel::SharedTokenSet::new();
+
+        let builder = mio_channel::ChannelBuilder::new().token_set(&set);
+
+        let (_tx0, mut rx0) = builder.clone().name("requests").build::<()>();
+
+        let (_tx1, mut rx1) = builder.name("responses").build::<()>();
+
+        poll.registry().register(&mut rx0, mio::Token(0), mio::Interest::READABLE)?;
+
+        let e = poll.registry().register(&mut rx0, mio::Token(1), mio::Interest::READABLE).unwrap_err();
+
+        assert!(e.to_string().contains("requests"));
+
+        let e = poll.registry().register(&mut rx1, mio::Token(0), mio::Interest::READABLE).unwrap_err();
+
+        assert!(e.to_string().contains("responses"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_recv_fast() -> Result<(), Box<dyn std::error::Error>> {
+        use mio_channel::RecvStatus;
+
+        let (tx, rx) = mio_channel::channel();
+
+        tx.send(1)?;
+
+        assert_eq!(rx.try_recv_fast(), RecvStatus::Message(1));
+
+        assert_eq!(rx.try_recv_fast(), RecvStatus::Empty);
+
+        drop(tx);
+
+        assert_eq!(rx.try_recv_fast(), RecvStatus::Disconnected);
+
+        let (tx, rx) = mio_channel::ChannelBuilder::new().coalesce(true).build();
+
+        tx.send(2)?;
+
+        assert_eq!(rx.try_recv_fast(), RecvStatus::Message(2));
+
+        assert_eq!(rx.try_recv_fast(), RecvStatus::Empty);
+
+        assert_eq!(rx.len(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_register_all() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(3);
+
+        let (tx0, mut rx0) = mio_channel::channel();
+
+        let (tx1, mut rx1) = mio_channel::channel();
+
+        let (tx2, mut rx2) = mio_channel::channel();
+
+        let mut receivers = [(&mut rx0, mio::Token(0)), (&mut rx1, mio::Token(1)), (&mut rx2, mio::Token(2))];
+
+        mio_channel::register_all(poll.registry(), &mut receivers)?;
+
+        for (i, tx) in [tx0, tx1, tx2].iter().enumerate() {
+            tx.send(i)?;
+        }
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        let mut tokens = events.iter().map(|event| event.token()).collect::<Vec<_>>();
+
+        tokens.sort();
+
+        assert_eq!(tokens, vec![mio::Token(0), mio::Token(1), mio::Token(2)]);
+
+        assert!(rx0.is_registered_with(poll.registry(), mio::Token(0)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_drain_and_close_bounds_grace_window() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        tx.send(0)?;
+
+        tx.send(1)?;
+
+        // It's received bypassing the receiver, so that it's still counted as pending.
+        assert_eq!(rx.as_ref().try_recv()?, 0);
+
+        assert_eq!(rx.drain_and_close(), vec![1]);
+
+        assert!(tx.send(2).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_as_ref_drift_settled_on_disconnect() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        tx.send(0)?;
+
+        assert_eq!(rx.as_ref().try_recv()?, 0);
+
+        assert_eq!(rx.len(), 1);
+
+        drop(tx);
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Disconnected));
+
+        assert!(rx.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_sender_races_with_old_senders() -> Result<(), Box<dyn std::error::Error>> {
+        let (old, mut rx) = mio_channel::channel();
+
+        let handler = std::thread::spawn(move || {
+            let mut i = 0;
+
+            while old.send(i).is_ok() {
+                i += 1;
+            }
+        });
+
+        while rx.is_empty() {
+            std::thread::yield_now();
+        }
+
+        let tx = rx.replace_sender();
+
+        handler.join().map_err(|_| "panicked")?;
+
+        // Nothing racing with it is left counted as pending.
+        assert!(rx.is_empty());
+
+        drop(tx);
+
+        assert!(rx.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_sender_hangs_up_apart_from_old_senders() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (old, mut rx) = mio_channel::channel::<()>();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let tx = rx.replace_sender();
+
+        let dropped = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let clone = dropped.clone();
+
+        rx.on_all_senders_dropped(move || clone.store(true, std::sync::atomic::Ordering::SeqCst));
+
+        drop(tx);
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().next().map(|event| event.token()), Some(CHANNEL));
+
+        assert!(dropped.load(std::sync::atomic::Ordering::SeqCst));
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Disconnected));
+
+        drop(old);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_hangs_up_apart_from_old_senders() -> Result<(), Box<dyn std::error::Error>> {
+        let (old, mut rx) = mio_channel::channel();
+
+        old.send(0)?;
+
+        let tx = rx.reset();
+
+        assert!(rx.is_empty());
+
+        drop(tx);
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Disconnected));
+
+        assert!(old.send(1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_register_beside_mio_waker() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(4);
+
+        let waker = mio::Waker::new(poll.registry(), mio::Token(9))?;
+
+        let (tx0, mut rx0) = mio_channel::channel();
+
+        let (tx1, mut rx1) = mio_channel::channel();
+
+        poll.registry().register(&mut rx0, mio::Token(0), mio::Interest::READABLE)?;
+
+        poll.registry().register(&mut rx1, mio::Token(1), mio::Interest::READABLE)?;
+
+        tx0.send(0)?;
+
+        tx1.send(1)?;
+
+        waker.wake()?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        let mut tokens = events.iter().map(|event| event.token()).collect::<Vec<_>>();
+
+        tokens.sort();
+
+        assert_eq!(tokens, vec![mio::Token(0), mio::Token(1), mio::Token(9)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wake_beyond_socket_buffer() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (_tx, mut rx) = mio_channel::channel::<()>();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let waker = rx.waker().ok_or("no waker")?;
+
+        for _ in 0..100_000 {
+            waker.wake()?;
+        }
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().map(|event| event.token()).collect::<Vec<_>>(), vec![CHANNEL]);
+
+        waker.wake()?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().map(|event| event.token()).collect::<Vec<_>>(), vec![CHANNEL]);
+
+        Ok(())
+    }
 }
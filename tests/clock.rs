@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use std::{sync::{atomic::{AtomicBool, Ordering}, mpsc::RecvTimeoutError, Arc}, time::{Duration, Instant}};
+
+    use mio_channel::{ChannelBuilder, ManualClock};
+
+    #[test]
+    fn test_recv_timeout_with_manual_clock() -> Result<(), Box<dyn std::error::Error>> {
+        let clock = Arc::new(ManualClock::new());
+
+        let (tx, rx) = ChannelBuilder::new().clock(clock.clone()).build::<()>();
+
+        let done = Arc::new(AtomicBool::new(false));
+
+        let handler = {
+            let clock = clock.clone();
+
+            let done = done.clone();
+
+            std::thread::spawn(move || {
+                while !done.load(Ordering::Acquire) {
+                    clock.advance(Duration::from_secs(60));
+
+                    std::thread::yield_now();
+                }
+            })
+        };
+
+        let now = Instant::now();
+
+        assert_eq!(rx.recv_timeout(Duration::from_secs(3600)), Err(RecvTimeoutError::Timeout));
+
+        assert!(now.elapsed() < Duration::from_secs(1));
+
+        done.store(true, Ordering::Release);
+
+        let _ = handler.join();
+
+        drop(tx);
+
+        Ok(())
+    }
+}
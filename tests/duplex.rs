@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    const CHANNEL: mio::Token = mio::Token(0);
+
+    #[test]
+    fn test_duplex() -> Result<(), Box<dyn std::error::Error>> {
+        let (mut client, mut server) = mio_channel::duplex::<&str, usize>();
+
+        let mut server_poll = mio::Poll::new()?;
+
+        server_poll.registry().register(&mut server, CHANNEL, mio::Interest::READABLE)?;
+
+        let handler = std::thread::spawn(move || -> std::io::Result<()> {
+            let mut events = mio::Events::with_capacity(2);
+
+            server_poll.poll(&mut events, None)?;
+
+            if let Ok(request) = server.try_recv() {
+                let _ = server.send(request.len());
+            }
+
+            Ok(())
+        });
+
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        poll.registry().register(&mut client, CHANNEL, mio::Interest::READABLE)?;
+
+        client.send("Hello world!")?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        assert_eq!(client.try_recv()?, 12);
+
+        let _ = handler.join();
+
+        Ok(())
+    }
+}
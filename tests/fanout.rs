@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use mio_channel::{MultiSender, Policy};
+
+    const CHANNEL: mio::Token = mio::Token(0);
+
+    #[test]
+    fn test_round_robin() -> Result<(), Box<dyn std::error::Error>> {
+        let mut polls = Vec::new();
+
+        let mut txs = Vec::new();
+
+        let mut rxs = Vec::new();
+
+        for _ in 0..3 {
+            let poll = mio::Poll::new()?;
+
+            let (tx, mut rx) = mio_channel::channel();
+
+            poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+            polls.push(poll);
+
+            txs.push(tx);
+
+            rxs.push(rx);
+        }
+
+        let tx = MultiSender::new(txs, Policy::RoundRobin);
+
+        for i in 0..30 {
+            tx.send(i)?;
+        }
+
+        let mut events = mio::Events::with_capacity(2);
+
+        for (poll, rx) in polls.iter_mut().zip(&rxs) {
+            poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+            assert!(!events.is_empty());
+
+            assert_eq!(rx.len(), 10);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_least_queued() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx0, rx0) = mio_channel::channel();
+
+        let (tx1, rx1) = mio_channel::channel();
+
+        tx0.send(0)?;
+
+        tx0.send(1)?;
+
+        let tx = MultiSender::new(vec![tx0, tx1], Policy::LeastQueued);
+
+        tx.send(2)?;
+
+        tx.send(3)?;
+
+        assert_eq!(rx0.len(), 2);
+
+        assert_eq!(rx1.len(), 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_drain_to_writer() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        for (i, s) in ["Hello", "world", "!"].into_iter().enumerate() {
+            tx.send((i, s.to_string()))?;
+        }
+
+        let mut buf = Vec::new();
+
+        assert_eq!(rx.drain_to_writer(&mut buf)?, 3);
+
+        let mut r = buf.as_slice();
+
+        assert_eq!(mio_channel::read_frame::<(usize, String), _>(&mut r)?, (0, "Hello".to_string()));
+
+        assert_eq!(mio_channel::read_frame::<(usize, String), _>(&mut r)?, (1, "world".to_string()));
+
+        assert_eq!(mio_channel::read_frame::<(usize, String), _>(&mut r)?, (2, "!".to_string()));
+
+        assert!(r.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_drain_to_writer_keeps_unwritten() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        for s in ["a", "bcdefgh"] {
+            tx.send(s.to_string())?;
+        }
+
+        // The first frame takes 4 bytes of the length, 8 bytes of the length of the string and 1 byte of it.
+        let mut buf = [0; 16];
+
+        let mut w = &mut buf[..];
+
+        assert!(rx.drain_to_writer(&mut w).is_err());
+
+        assert_eq!(rx.len(), 1);
+
+        assert_eq!(rx.try_recv()?, "bcdefgh");
+
+        Ok(())
+    }
+}
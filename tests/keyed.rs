@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod tests {
+    const CHANNEL: mio::Token = mio::Token(0);
+
+    #[test]
+    fn test_keyed_latest_channel() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = mio_channel::keyed_latest_channel(|(key, _): &(&str, i32)| *key);
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        for update in [("a", 0), ("b", 0), ("a", 1), ("b", 1), ("a", 2)] {
+            tx.send(update)?;
+        }
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().next().map(|event| event.token()), Some(CHANNEL));
+
+        assert_eq!(rx.len(), 2);
+
+        assert_eq!(rx.try_recv()?, ("a", 2));
+
+        assert_eq!(rx.try_recv()?, ("b", 1));
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Empty));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod tests {
+    const CHANNEL: mio::Token = mio::Token(0);
+
+    #[test]
+    fn test_latest_channel() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = mio_channel::latest_channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        for i in 0..5 {
+            tx.send(i)?;
+        }
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().next().map(|event| event.token()), Some(CHANNEL));
+
+        assert_eq!(rx.try_recv()?, 4);
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Empty));
+
+        drop(tx);
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Disconnected));
+
+        Ok(())
+    }
+}
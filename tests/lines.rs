@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_drain_as_lines() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        for line in ["a", "bc", "def"] {
+            tx.send(line.to_string())?;
+        }
+
+        let mut buf = Vec::new();
+
+        assert_eq!(rx.drain_as_lines(&mut buf)?, 3);
+
+        assert_eq!(buf, b"a\nbc\ndef\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_drain_as_lines_keeps_unwritten() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        for line in ["a", "bc"] {
+            tx.send(line.to_string())?;
+        }
+
+        let mut buf = [0; 3];
+
+        let mut w = &mut buf[..];
+
+        assert!(rx.drain_as_lines(&mut w).is_err());
+
+        assert_eq!(rx.len(), 1);
+
+        assert_eq!(rx.try_recv()?, "bc");
+
+        Ok(())
+    }
+}
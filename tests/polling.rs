@@ -0,0 +1,347 @@
+#[cfg(test)]
+mod tests {
+    use mio_channel::Ready;
+
+    const CHANNEL: mio::Token = mio::Token(0);
+
+    const SOCKET: mio::Token = mio::Token(1);
+
+    #[test]
+    fn test_recv_or_event() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let mut socket = mio::net::UdpSocket::bind("127.0.0.1:0".parse()?)?;
+
+        poll.registry().register(&mut socket, SOCKET, mio::Interest::READABLE)?;
+
+        let timeout = Some(std::time::Duration::from_millis(1000));
+
+        std::net::UdpSocket::bind("127.0.0.1:0")?.send_to(b"ping", socket.local_addr()?)?;
+
+        assert_eq!(rx.recv_or_event(&mut poll, &mut events, CHANNEL, timeout)?, Ready::Other(SOCKET));
+
+        tx.send("Hello world!")?;
+
+        assert_eq!(rx.recv_or_event(&mut poll, &mut events, CHANNEL, timeout)?, Ready::Message("Hello world!"));
+
+        let timeout = Some(std::time::Duration::from_millis(100));
+
+        assert_eq!(rx.recv_or_event(&mut poll, &mut events, CHANNEL, timeout)?, Ready::Timeout);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_timeout_polled() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let timeout = std::time::Duration::from_millis(100);
+
+        assert_eq!(rx.recv_timeout_polled(&mut poll, &mut events, CHANNEL, timeout)?, None);
+
+        let handler = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+
+            let _ = tx.send("Hello world!");
+        });
+
+        let timeout = std::time::Duration::from_millis(1000);
+
+        assert_eq!(rx.recv_timeout_polled(&mut poll, &mut events, CHANNEL, timeout)?, Some("Hello world!"));
+
+        let _ = handler.join();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wait_for() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let handler = std::thread::spawn(move || {
+            for i in 0..5 {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+
+                let _ = tx.send(i);
+            }
+        });
+
+        let timeout = Some(std::time::Duration::from_millis(1000));
+
+        assert_eq!(rx.wait_for(&mut poll, &mut events, CHANNEL, timeout, |&i| i == 3)?, Some(3));
+
+        let _ = handler.join();
+
+        assert_eq!(rx.try_recv()?, 4);
+
+        let timeout = Some(std::time::Duration::from_millis(100));
+
+        assert_eq!(rx.wait_for(&mut poll, &mut events, CHANNEL, timeout, |&i| i == 3)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_timed() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let handler = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+
+            tx.send("Hello world!")
+        });
+
+        let (t, timing) = rx.recv_timed(&mut poll, &mut events, CHANNEL, Some(std::time::Duration::from_millis(1000)))?;
+
+        handler.join().map_err(|_| "panicked")??;
+
+        assert_eq!(t, Some("Hello world!"));
+
+        assert!(timing.wait >= std::time::Duration::from_millis(10));
+
+        assert!(timing.drain < timing.wait);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_exact() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let handler = std::thread::spawn(move || {
+            for i in 0..4 {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+
+                tx.send(i)?;
+            }
+
+            Ok::<_, std::sync::mpsc::SendError<i32>>(tx)
+        });
+
+        let timeout = Some(std::time::Duration::from_millis(1000));
+
+        assert_eq!(rx.recv_exact(3, &mut poll, &mut events, CHANNEL, timeout)?, Ok(vec![0, 1, 2]));
+
+        let _tx = handler.join().map_err(|_| "panicked")??;
+
+        let timeout = Some(std::time::Duration::from_millis(100));
+
+        assert_eq!(rx.recv_exact(2, &mut poll, &mut events, CHANNEL, timeout)?, Err(vec![3]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_poll_timeout_auto() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (_tx, mut rx) = mio_channel::channel::<()>();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let started = std::time::Instant::now();
+
+        let deadlines = [started + std::time::Duration::from_secs(10), started + std::time::Duration::from_millis(50)];
+
+        assert_eq!(rx.poll_timeout_auto(&mut poll, &mut events, CHANNEL, &deadlines)?, Ready::Timeout);
+
+        assert!(started.elapsed() >= std::time::Duration::from_millis(50));
+
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+        assert_eq!(rx.poll_timeout_auto(&mut poll, &mut events, CHANNEL, &[started])?, Ready::Timeout);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_if_ready() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx0, mut rx0) = mio_channel::channel();
+
+        let (_tx1, mut rx1) = mio_channel::channel::<&str>();
+
+        poll.registry().register(&mut rx0, CHANNEL, mio::Interest::READABLE)?;
+
+        poll.registry().register(&mut rx1, SOCKET, mio::Interest::READABLE)?;
+
+        tx0.send("Hello world!")?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(rx1.recv_if_ready(&events), None);
+
+        assert_eq!(rx0.recv_if_ready(&events), Some("Hello world!"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_group() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        for t in ["a", "b", "MARKER", "c", "d"] {
+            tx.send(t)?;
+        }
+
+        let timeout = Some(std::time::Duration::from_millis(100));
+
+        assert_eq!(rx.recv_group(|t| *t == "MARKER", &mut poll, &mut events, CHANNEL, timeout)?, Some(vec!["a", "b"]));
+
+        assert_eq!(rx.recv_group(|t| *t == "MARKER", &mut poll, &mut events, CHANNEL, timeout)?, None);
+
+        assert_eq!(rx.try_recv()?, "c");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_zip() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx0, mut rx0) = mio_channel::channel();
+
+        let (tx1, mut rx1) = mio_channel::channel();
+
+        poll.registry().register(&mut rx0, CHANNEL, mio::Interest::READABLE)?;
+
+        poll.registry().register(&mut rx1, SOCKET, mio::Interest::READABLE)?;
+
+        tx0.send("a")?;
+
+        let timeout = Some(std::time::Duration::from_millis(100));
+
+        assert_eq!(mio_channel::recv_zip(&rx0, &rx1, &mut poll, &mut events, (CHANNEL, SOCKET), timeout)?, None);
+
+        let handler = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+
+            tx1.send(1)
+        });
+
+        let timeout = Some(std::time::Duration::from_millis(1000));
+
+        assert_eq!(mio_channel::recv_zip(&rx0, &rx1, &mut poll, &mut events, (CHANNEL, SOCKET), timeout)?, Some(("a", 1)));
+
+        handler.join().map_err(|_| "panicked")??;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_retry_on_spurious() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let timeout = Some(std::time::Duration::from_millis(1000));
+
+        let handler = std::thread::spawn(move || {
+            for _ in 0..3 {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+
+                tx.wake();
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(20));
+
+            tx.send("Hello world!")?;
+
+            Ok::<_, std::sync::mpsc::SendError<&str>>(tx)
+        });
+
+        assert_eq!(rx.recv_retry_on_spurious(&mut poll, &mut events, CHANNEL, 5, timeout)?, Some("Hello world!"));
+
+        let tx = handler.join().map_err(|_| "panicked")??;
+
+        tx.wake();
+
+        let e = rx.recv_retry_on_spurious(&mut poll, &mut events, CHANNEL, 0, timeout).unwrap_err();
+
+        assert_eq!(e.kind(), std::io::ErrorKind::WouldBlock);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recv_timeout_polled_leaves_other_events() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        let mut socket = mio::net::UdpSocket::bind("127.0.0.1:0".parse()?)?;
+
+        poll.registry().register(&mut socket, SOCKET, mio::Interest::READABLE)?;
+
+        std::net::UdpSocket::bind("127.0.0.1:0")?.send_to(b"ping", socket.local_addr()?)?;
+
+        let timeout = std::time::Duration::from_millis(1000);
+
+        assert_eq!(rx.recv_timeout_polled(&mut poll, &mut events, CHANNEL, timeout)?, None);
+
+        assert!(events.iter().any(|event| event.token() == SOCKET));
+
+        tx.send("Hello world!")?;
+
+        assert_eq!(rx.recv_timeout_polled(&mut poll, &mut events, CHANNEL, timeout)?, Some("Hello world!"));
+
+        let timeout = std::time::Duration::from_millis(50);
+
+        assert_eq!(rx.recv_timeout_polled(&mut poll, &mut events, CHANNEL, timeout)?, None);
+
+        assert!(events.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod tests {
+    use mio_channel::WakerPool;
+
+    #[test]
+    fn test_channel_affine() -> Result<(), Box<dyn std::error::Error>> {
+        let mut polls = [mio::Poll::new()?, mio::Poll::new()?];
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let mut pool = WakerPool::new();
+
+        for (i, poll) in polls.iter().enumerate() {
+            assert_eq!(pool.add_node(poll.registry(), mio::Token(i))?, i);
+        }
+
+        let (tx, rx) = pool.channel_affine(3);
+
+        tx.send("Hello world!")?;
+
+        polls[0].poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert!(events.is_empty());
+
+        polls[1].poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        assert_eq!(events.iter().next().map(|event| event.token()), Some(mio::Token(1)));
+
+        assert_eq!(rx.try_recv()?, "Hello world!");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_sender_pool() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        let pool = mio_channel::SenderPool::new(tx, 2);
+
+        let first = pool.acquire().ok_or("exhausted")?;
+
+        let second = pool.acquire().ok_or("exhausted")?;
+
+        assert!(pool.acquire().is_none());
+
+        assert_eq!(pool.in_use(), 2);
+
+        first.send(0)?;
+
+        second.send(1)?;
+
+        drop(first);
+
+        let third = pool.acquire().ok_or("exhausted")?;
+
+        third.send(2)?;
+
+        assert_eq!(rx.recv_many_timeout(3, std::time::Duration::from_millis(10)).0, vec![0, 1, 2]);
+
+        Ok(())
+    }
+}
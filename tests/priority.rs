@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    const CHANNEL: mio::Token = mio::Token(0);
+
+    const PRIORITY: mio::Token = mio::Token(1);
+
+    #[test]
+    fn test_priority_channel() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx, mut rx) = mio_channel::priority_channel();
+
+        rx.register(poll.registry(), CHANNEL, PRIORITY)?;
+
+        tx.send_priority("urgent")?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().map(|event| event.token()).collect::<Vec<_>>(), vec![PRIORITY]);
+
+        tx.send("normal")?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().map(|event| event.token()).collect::<Vec<_>>(), vec![CHANNEL]);
+
+        tx.send_priority("later")?;
+
+        assert_eq!(rx.try_recv()?, "urgent");
+
+        assert_eq!(rx.try_recv()?, "later");
+
+        assert_eq!(rx.try_recv()?, "normal");
+
+        drop(tx);
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Disconnected));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod tests {
+    const CHANNEL: mio::Token = mio::Token(0);
+
+    #[test]
+    fn test_ring_channel() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = mio_channel::ring_channel(3);
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        for i in 0..5 {
+            tx.send(i)?;
+        }
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().next().map(|event| event.token()), Some(CHANNEL));
+
+        assert_eq!(rx.len(), 3);
+
+        for i in 2..5 {
+            assert_eq!(rx.try_recv()?, i);
+        }
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Empty));
+
+        drop(tx);
+
+        assert_eq!(rx.try_recv(), Err(std::sync::mpsc::TryRecvError::Disconnected));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ring_channel_on_overflow() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::ring_channel(1);
+
+        let evicted = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let clone = evicted.clone();
+
+        rx.on_overflow(move |t| clone.lock().unwrap().push(t));
+
+        for i in 0..3 {
+            tx.send(i)?;
+        }
+
+        assert_eq!(*evicted.lock().unwrap(), vec![0, 1]);
+
+        assert_eq!(rx.try_recv()?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ring_channel_eviction_without_wake() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = mio_channel::ring_channel(1);
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        tx.send(0)?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().count(), 1);
+
+        tx.send(1)?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(events.iter().count(), 0);
+
+        assert_eq!(rx.try_recv()?, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    const FIRST: mio::Token = mio::Token(0);
+
+    const SECOND: mio::Token = mio::Token(1);
+
+    #[test]
+    fn test_dispatch() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (tx0, rx0) = mio_channel::channel();
+
+        let (_tx1, rx1) = mio_channel::channel();
+
+        let first = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+
+        let second = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+
+        let mut router = mio_channel::Router::new();
+
+        let handled = first.clone();
+
+        router.route(poll.registry(), rx0, FIRST, move |t| handled.borrow_mut().push(t))?;
+
+        let handled = second.clone();
+
+        router.route(poll.registry(), rx1, SECOND, move |t| handled.borrow_mut().push(t))?;
+
+        tx0.send(0)?;
+
+        tx0.send(1)?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(router.dispatch(&events), 2);
+
+        assert_eq!(*first.borrow(), vec![0, 1]);
+
+        assert!(second.borrow().is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_steal() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        for i in 0..1000 {
+            tx.send(i)?;
+        }
+
+        drop(tx);
+
+        let rx = rx.into_shared();
+
+        let handlers: Vec<_> = (0..4).map(|_| {
+            let rx = rx.clone();
+
+            std::thread::spawn(move || {
+                let mut stolen = Vec::new();
+
+                while !rx.is_empty() {
+                    stolen.extend(rx.steal());
+                }
+
+                stolen
+            })
+        }).collect();
+
+        let mut total = 0;
+
+        let mut seen = HashSet::new();
+
+        for handler in handlers {
+            for i in handler.join().map_err(|_| "panicked")? {
+                total += 1;
+
+                assert!(seen.insert(i));
+            }
+        }
+
+        assert_eq!(total, 1000);
+
+        assert_eq!(seen.len(), 1000);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use futures::SinkExt;
+
+    #[test]
+    fn test_sync_sink() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::sync_channel(2);
+
+        let mut sink = tx.into_sink();
+
+        let handler = std::thread::spawn(move || {
+            futures::executor::block_on(async {
+                for i in 0..10 {
+                    sink.send(i).await?;
+                }
+
+                Ok::<_, std::sync::mpsc::SendError<i32>>(())
+            })
+        });
+
+        let mut received = Vec::new();
+
+        while received.len() < 10 {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+
+            // The producer is throttled by the bound, however fast it is.
+            assert!(rx.len() <= 2);
+
+            if let Ok(i) = rx.try_recv() {
+                received.push(i);
+            }
+        }
+
+        handler.join().map_err(|_| "panicked")??;
+
+        assert_eq!(received, (0..10).collect::<Vec<_>>());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_sinks_sharing_channel() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::sync_channel(1);
+
+        let handlers = [(0, tx.clone()), (10, tx)].map(|(base, tx)| {
+            let mut sink = tx.into_sink();
+
+            std::thread::spawn(move || {
+                futures::executor::block_on(async {
+                    for i in base..base + 5 {
+                        sink.send(i).await?;
+                    }
+
+                    Ok::<_, std::sync::mpsc::SendError<i32>>(())
+                })
+            })
+        });
+
+        let mut received = Vec::new();
+
+        for _ in 0..10 {
+            received.push(rx.recv_timeout(std::time::Duration::from_millis(1000))?);
+        }
+
+        for handler in handlers {
+            handler.join().map_err(|_| "panicked")??;
+        }
+
+        received.sort();
+
+        assert_eq!(received, (0..5).chain(10..15).collect::<Vec<_>>());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    #[test]
+    fn test_batch_stream() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::channel();
+
+        let mut stream = rx.into_batch_stream();
+
+        for i in 0..5 {
+            tx.send(i)?;
+        }
+
+        assert_eq!(futures::executor::block_on(stream.next()), Some(vec![0, 1, 2, 3, 4]));
+
+        let handler = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+
+            tx.send(5)
+        });
+
+        assert_eq!(futures::executor::block_on(stream.next()), Some(vec![5]));
+
+        handler.join().map_err(|_| "panicked")??;
+
+        assert_eq!(futures::executor::block_on(stream.next()), None);
+
+        Ok(())
+    }
+}
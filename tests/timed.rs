@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_timed_channel() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::timed_channel();
+
+        tx.send("Hello world!")?;
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        let (t, dwell) = rx.try_recv_timed().ok_or("empty")?;
+
+        assert_eq!(t, "Hello world!");
+
+        assert!(dwell >= std::time::Duration::from_millis(20));
+
+        let histogram = rx.histogram_snapshot();
+
+        assert_eq!(histogram.counts.iter().sum::<usize>(), 1);
+
+        // It lands in the bucket up to 100 milliseconds.
+        assert_eq!(histogram.counts[5], 1);
+
+        assert_eq!(rx.try_recv_timed(), None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod tests {
+    const CHANNEL: mio::Token = mio::Token(0);
+
+    #[test]
+    fn test_register_typed() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        let token = rx.register_typed(poll.registry(), CHANNEL)?;
+
+        assert_eq!(token.token(), CHANNEL);
+
+        tx.send("a")?;
+
+        tx.send("b")?;
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(rx.drain_for(token, &events), vec!["a", "b"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mismatched_token() {
+        let t = trybuild::TestCases::new();
+
+        t.compile_fail("tests/ui/*.rs");
+    }
+}
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests {
+    const CHANNEL: mio::Token = mio::Token(0);
+
+    use mio_channel::Pipe;
+
+    #[tokio::test]
+    async fn test_pipe_to_tokio() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(1);
+
+        let (tx, mut rx) = mio_channel::channel();
+
+        let (tokio_tx, mut tokio_rx) = tokio::sync::mpsc::channel(2);
+
+        poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+        for i in 0..3 {
+            tx.send(i)?;
+        }
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+        assert_eq!(rx.pipe_to_tokio(&tokio_tx), Pipe::Full(2));
+
+        assert_eq!(tokio_rx.recv().await, Some(0));
+
+        // Nothing wakes up the poll for the room made, so that it's retried on the timeout.
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(10)))?;
+
+        assert!(events.is_empty());
+
+        assert_eq!(rx.pipe_to_tokio(&tokio_tx), Pipe::Drained(1));
+
+        assert_eq!(tokio_rx.recv().await, Some(1));
+
+        assert_eq!(tokio_rx.recv().await, Some(2));
+
+        drop(tx);
+
+        assert_eq!(rx.pipe_to_tokio(&tokio_tx), Pipe::Disconnected(0));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,15 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let poll = mio::Poll::new()?;
+
+    let events = mio::Events::with_capacity(1);
+
+    let (_tx0, mut rx0) = mio_channel::channel::<u32>();
+
+    let (_tx1, rx1) = mio_channel::channel::<String>();
+
+    let token = rx0.register_typed(poll.registry(), mio::Token(0))?;
+
+    rx1.drain_for(token, &events);
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    const CHANNEL: mio::Token = mio::Token(0);
+
+    #[test]
+    fn test_work_channel() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx) = mio_channel::work_channel();
+
+        let mut handlers = Vec::new();
+
+        for mut rx in [rx.clone(), rx] {
+            let mut poll = mio::Poll::new()?;
+
+            poll.registry().register(&mut rx, CHANNEL, mio::Interest::READABLE)?;
+
+            handlers.push(std::thread::spawn(move || -> std::io::Result<Vec<i32>> {
+                let mut events = mio::Events::with_capacity(1);
+
+                let mut received = Vec::new();
+
+                loop {
+                    poll.poll(&mut events, Some(std::time::Duration::from_millis(100)))?;
+
+                    loop {
+                        match rx.try_recv() {
+                            Ok(t) => received.push(t),
+                            Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                            Err(std::sync::mpsc::TryRecvError::Disconnected) => return Ok(received),
+                        }
+                    }
+                }
+            }));
+        }
+
+        for i in 0..100 {
+            tx.send(i)?;
+        }
+
+        drop(tx);
+
+        let mut received = Vec::new();
+
+        for handler in handlers {
+            received.extend(handler.join().map_err(|_| "panicked")??);
+        }
+
+        received.sort();
+
+        assert_eq!(received, (0..100).collect::<Vec<_>>());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_work_sender_fails_without_receivers() -> Result<(), Box<dyn std::error::Error>> {
+        let (tx, rx0) = mio_channel::work_channel();
+
+        let rx1 = rx0.clone();
+
+        tx.send(0)?;
+
+        drop(rx0);
+
+        tx.send(1)?;
+
+        drop(rx1);
+
+        assert_eq!(tx.send(2), Err(std::sync::mpsc::SendError(2)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    const SENDER: mio::Token = mio::Token(0);
+
+    #[test]
+    fn test_writable_channel() -> Result<(), Box<dyn std::error::Error>> {
+        let mut poll = mio::Poll::new()?;
+
+        let mut events = mio::Events::with_capacity(2);
+
+        let (mut tx, rx) = mio_channel::writable_channel(2);
+
+        assert_eq!(tx.supported_interest(), mio::Interest::READABLE);
+
+        poll.registry().register(&mut tx, SENDER, mio::Interest::WRITABLE)?;
+
+        tx.try_send(0)?;
+
+        tx.try_send(1)?;
+
+        assert!(matches!(tx.try_send(2), Err(std::sync::mpsc::TrySendError::Full(2))));
+
+        let handler = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+
+            let _ = rx.try_recv();
+
+            rx
+        });
+
+        poll.poll(&mut events, Some(std::time::Duration::from_millis(1000)))?;
+
+        let event = events.iter().next().ok_or("no event")?;
+
+        assert_eq!(event.token(), SENDER);
+
+        // It's caused by a waker, whose events are always readable.
+        assert!(event.is_readable());
+
+        assert!(!event.is_writable());
+
+        tx.try_send(2)?;
+
+        let rx = handler.join().map_err(|_| "panicked")?;
+
+        assert_eq!(rx.try_recv()?, 1);
+
+        Ok(())
+    }
+}